@@ -3,6 +3,7 @@
 use crate::Level;
 use crate::Substitution;
 use crate::SubstitutionPart;
+use crate::SuggestionCategory;
 use crate::SuggestionStyle;
 use rustc_lint_defs::Applicability;
 use rustc_span::{MultiSpan, Span, DUMMY_SP};
@@ -303,6 +304,7 @@ pub fn multipart_suggestion(
             msg: msg.to_owned(),
             style: SuggestionStyle::ShowCode,
             applicability,
+            category: None,
         });
         self
     }
@@ -328,6 +330,7 @@ pub fn multipart_suggestions(
             msg: msg.to_owned(),
             style: SuggestionStyle::ShowCode,
             applicability,
+            category: None,
         });
         self
     }
@@ -354,6 +357,7 @@ pub fn tool_only_multipart_suggestion(
             msg: msg.to_owned(),
             style: SuggestionStyle::CompletelyHidden,
             applicability,
+            category: None,
         });
         self
     }
@@ -408,6 +412,7 @@ pub fn span_suggestion_with_style(
             msg: msg.to_owned(),
             style,
             applicability,
+            category: None,
         });
         self
     }
@@ -446,10 +451,24 @@ pub fn span_suggestions(
             msg: msg.to_owned(),
             style: SuggestionStyle::ShowCode,
             applicability,
+            category: None,
         });
         self
     }
 
+    /// Tags the suggestions added since the diagnostic had `len` of them with `category`, so
+    /// that tools reading the JSON output can tell what kind of fix each one is.
+    pub fn categorize_suggestions_since(
+        &mut self,
+        len: usize,
+        category: SuggestionCategory,
+    ) -> &mut Self {
+        for suggestion in &mut self.suggestions[len..] {
+            suggestion.category = Some(category);
+        }
+        self
+    }
+
     /// Prints out a message with a suggested edit of the code. If the suggestion is presented
     /// inline, it will only show the message and not the suggestion.
     ///
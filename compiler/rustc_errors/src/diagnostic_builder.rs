@@ -1,5 +1,5 @@
 use crate::{Diagnostic, DiagnosticId, DiagnosticStyledString};
-use crate::{Handler, Level, StashKey};
+use crate::{Handler, Level, StashKey, SuggestionCategory};
 use rustc_lint_defs::Applicability;
 
 use rustc_span::{MultiSpan, Span};
@@ -400,6 +400,11 @@ pub fn tool_only_span_suggestion(
 
     forward!(pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self);
     forward!(pub fn code(&mut self, s: DiagnosticId) -> &mut Self);
+    forward!(pub fn categorize_suggestions_since(
+        &mut self,
+        len: usize,
+        category: SuggestionCategory,
+    ) -> &mut Self);
 
     /// Allow attaching suggestions this diagnostic.
     /// If this is set to `false`, then any suggestions attached with the `span_suggestion_*`
@@ -14,7 +14,7 @@
 use crate::emitter::{Emitter, HumanReadableErrorType};
 use crate::registry::Registry;
 use crate::DiagnosticId;
-use crate::{CodeSuggestion, SubDiagnostic};
+use crate::{CodeSuggestion, SubDiagnostic, SuggestionCategory};
 use rustc_lint_defs::{Applicability, FutureBreakage};
 
 use rustc_data_structures::sync::Lrc;
@@ -205,6 +205,8 @@ struct DiagnosticSpan {
     suggested_replacement: Option<String>,
     /// If the suggestion is approximate
     suggestion_applicability: Option<Applicability>,
+    /// What kind of fix the suggestion is, if the compiler tagged it
+    suggestion_category: Option<&'static str>,
     /// Macro invocations that created the code at this span, if any.
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
@@ -389,6 +391,7 @@ fn from_span_full(
             text: DiagnosticSpanLine::from_span(span, je),
             suggested_replacement: suggestion.map(|x| x.0.clone()),
             suggestion_applicability: suggestion.map(|x| x.1),
+            suggestion_category: None,
             expansion: backtrace_step,
             label,
         }
@@ -409,11 +412,13 @@ fn from_suggestion(suggestion: &CodeSuggestion, je: &JsonEmitter) -> Vec<Diagnos
                 substitution.parts.iter().map(move |suggestion_inner| {
                     let span_label =
                         SpanLabel { span: suggestion_inner.span, is_primary: true, label: None };
-                    DiagnosticSpan::from_span_label(
+                    let mut span = DiagnosticSpan::from_span_label(
                         span_label,
                         Some((&suggestion_inner.snippet, suggestion.applicability)),
                         je,
-                    )
+                    );
+                    span.suggestion_category = suggestion.category.map(SuggestionCategory::as_str);
+                    span
                 })
             })
             .collect()
@@ -106,6 +106,56 @@ pub struct CodeSuggestion {
     /// which are useful for users but not useful for
     /// tools like rustfix
     pub applicability: Applicability,
+    /// What kind of fix this is, so that tools consuming JSON output can group related
+    /// suggestions.
+    pub category: Option<SuggestionCategory>,
+}
+
+/// The kinds of fixes that suggestions can be tagged with in JSON output.
+///
+/// Suggestions that restructure code rather than fix up a single value are deliberately left
+/// uncategorized: returning `impl Trait` or a boxed trait object, changing a closure's arguments
+/// to match the expected count, adding `move` to a closure, moving an unsized tuple element,
+/// relaxing a bound with `?Sized` or removing one from an impl method, adding an `impl` for a
+/// local type, and the suggestions made while explaining where an obligation came from. So are the type annotations suggested for
+/// ambiguity errors, which don't fix a value but pin down what it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum SuggestionCategory {
+    /// Get at the value a trait bound wants from inside another one: a field, the `Ok` or `Some`
+    /// value, or the value behind a lock.
+    Access,
+    /// Borrow an argument to satisfy a trait bound.
+    Borrow,
+    /// Call a function or closure to use its output.
+    Call,
+    /// Change the mutability of a reference.
+    ChangeMut,
+    /// Add a bound to a type parameter or a trait object.
+    ConstrainParam,
+    /// Convert a value into another type that satisfies a trait bound.
+    Convert,
+    /// Derive a trait on a local type.
+    Derive,
+    /// Remove leading `&`-references.
+    RemoveReference,
+    /// Swap two type arguments that were written the wrong way around.
+    SwapTypeArgs,
+}
+
+impl SuggestionCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuggestionCategory::Access => "access",
+            SuggestionCategory::Borrow => "borrow",
+            SuggestionCategory::Call => "call",
+            SuggestionCategory::ChangeMut => "change-mut",
+            SuggestionCategory::ConstrainParam => "constrain-param",
+            SuggestionCategory::Convert => "convert",
+            SuggestionCategory::Derive => "derive",
+            SuggestionCategory::RemoveReference => "remove-reference",
+            SuggestionCategory::SwapTypeArgs => "swap-type-args",
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Hash, Encodable, Decodable)]
@@ -72,6 +72,33 @@ pub struct FulfillmentError<'tcx> {
     pub points_at_arg_span: bool,
 }
 
+/// Diagnostics only: a note, label or suggestion explaining why a trait obligation doesn't hold,
+/// for tools that present these on their own rather than as part of a rustc error.
+#[derive(Clone, Debug)]
+pub struct TraitExplanation<'tcx> {
+    /// The cause of the obligation being explained.
+    pub cause_code: ObligationCauseCode<'tcx>,
+    pub kind: TraitExplanationKind,
+    pub message: String,
+    /// The spans the explanation points at, if any.
+    pub spans: Vec<Span>,
+    /// The spans to replace along with their replacements, if the explanation is a suggestion.
+    pub edits: Vec<(Span, String)>,
+}
+
+/// How a `TraitExplanation` would have been shown as part of a rustc error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraitExplanationKind {
+    /// The error's primary message.
+    Message,
+    /// A label on one of the spans the error points at.
+    Label,
+    Note,
+    Help,
+    /// A suggested edit, with the message that would introduce it.
+    Suggestion,
+}
+
 #[derive(Clone)]
 pub enum FulfillmentErrorCode<'tcx> {
     CodeSelectionError(SelectionError<'tcx>),
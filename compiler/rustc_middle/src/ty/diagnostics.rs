@@ -72,6 +72,10 @@ pub fn is_suggestable(&self) -> bool {
     }
 }
 
+/// Number of bounds on a type parameter past which we suggest adding a `where` clause rather
+/// than appending to the parameter's bounds.
+const MANY_BOUNDS: usize = 3;
+
 /// Suggest restricting a type param with a new bound.
 pub fn suggest_constraining_type_param(
     tcx: TyCtxt<'_>,
@@ -80,6 +84,17 @@ pub fn suggest_constraining_type_param(
     param_name: &str,
     constraint: &str,
     def_id: Option<DefId>,
+) -> bool {
+    suggest_constraining_type_params(tcx, generics, err, param_name, &[(constraint, def_id)])
+}
+
+/// Suggest restricting a type param with several new bounds at once.
+pub fn suggest_constraining_type_params(
+    tcx: TyCtxt<'_>,
+    generics: &hir::Generics<'_>,
+    err: &mut DiagnosticBuilder<'_>,
+    param_name: &str,
+    constraints: &[(&str, Option<DefId>)],
 ) -> bool {
     let param = generics.params.iter().find(|p| p.name.ident().as_str() == param_name);
 
@@ -94,19 +109,18 @@ pub fn suggest_constraining_type_param(
     let msg_restrict_type_further =
         format!("consider further restricting type parameter `{}`", param_name);
 
-    if def_id == tcx.lang_items().sized_trait() {
+    let (sized, constraints): (Vec<_>, Vec<_>) = constraints
+        .iter()
+        .copied()
+        .partition(|(_, def_id)| *def_id == tcx.lang_items().sized_trait());
+    if let Some((constraint, _)) = sized.first() {
         // Type parameters are already `Sized` by default.
         err.span_label(param.span, &format!("this type parameter needs to be `{}`", constraint));
-        return true;
     }
-    let mut suggest_restrict = |span| {
-        err.span_suggestion_verbose(
-            span,
-            MSG_RESTRICT_BOUND_FURTHER,
-            format!(" + {}", constraint),
-            Applicability::MachineApplicable,
-        );
-    };
+    if constraints.is_empty() {
+        return !sized.is_empty();
+    }
+    let constraints = &constraints[..];
 
     if param_name.starts_with("impl ") {
         // If there's an `impl Trait` used in argument position, suggest
@@ -123,15 +137,57 @@ pub fn suggest_constraining_type_param(
         //             --------
         //             |
         //             replace with: `impl Foo + Bar`
+        //
+        // Traits the `impl Trait` already names are not repeated, so `impl Foo` missing both
+        // `Foo` and `Bar` is still restricted to `impl Foo + Bar`.
+        let existing = param
+            .bounds
+            .iter()
+            .filter_map(|bound| bound.trait_ref()?.trait_def_id())
+            .collect::<Vec<_>>();
+        let mut added: Vec<&str> = vec![];
+        for &(constraint, def_id) in constraints {
+            if def_id.map_or(false, |def_id| existing.contains(&def_id))
+                || added.contains(&constraint)
+            {
+                continue;
+            }
+            added.push(constraint);
+        }
+        if added.is_empty() {
+            return false;
+        }
 
-        suggest_restrict(param.span.shrink_to_hi());
+        err.span_suggestion_verbose(
+            param.span.shrink_to_hi(),
+            MSG_RESTRICT_BOUND_FURTHER,
+            format!(" + {}", added.join(" + ")),
+            Applicability::MachineApplicable,
+        );
         return true;
     }
 
+    let constraint =
+        constraints.iter().map(|&(constraint, _)| constraint).collect::<Vec<_>>().join(" + ");
+    let mut suggest_restrict = |span| {
+        err.span_suggestion_verbose(
+            span,
+            MSG_RESTRICT_BOUND_FURTHER,
+            format!(" + {}", constraint),
+            Applicability::MachineApplicable,
+        );
+    };
+
+    // Given `fn foo<T: Foo + Bar + Baz>(t: T)`, appending yet another bound to the parameter gets
+    // hard to read, so suggest introducing a `where` clause instead.
+    let has_many_bounds = param.bounds.len() >= MANY_BOUNDS
+        && !generics.where_clause.span_for_predicates_or_empty_place().is_dummy();
+
     if generics.where_clause.predicates.is_empty()
         // Given `trait Base<T = String>: Super<T>` where `T: Copy`, suggest restricting in the
         // `where` clause instead of `trait Base<T: Copy = String>: Super<T>`.
         && !matches!(param.kind, hir::GenericParamKind::Type { default: Some(_), .. })
+        && !has_many_bounds
     {
         if let Some(bounds_span) = param.bounds_span() {
             // If user has provided some bounds, suggest restricting them:
@@ -213,12 +269,23 @@ pub fn suggest_constraining_type_param(
         //    Suggestion:
         //      trait Foo<T=()> where T: Zar {... }
         //                     - insert: `where T: Zar`
+        //
+        // or because the generic parameter already has many bounds:
+        //
+        //    Message:
+        //      fn foo<T: Foo + Bar + Baz>(t: T) {... }
+        //             - help: consider further restricting this type parameter with `where T: Zar`
+        //
+        //    Suggestion:
+        //      fn foo<T: Foo + Bar + Baz>(t: T) where T: Zar {... }
+        //                                      - insert: `where T: Zar`
 
-        if matches!(param.kind, hir::GenericParamKind::Type { default: Some(_), .. })
+        if (matches!(param.kind, hir::GenericParamKind::Type { default: Some(_), .. })
+            || has_many_bounds)
             && generics.where_clause.predicates.len() == 0
         {
             // Suggest a bound, but there is no existing `where` clause *and* the type param has a
-            // default (`<T=Foo>`), so we suggest adding `where T: Bar`.
+            // default (`<T=Foo>`) or already many bounds, so we suggest adding `where T: Bar`.
             err.span_suggestion_verbose(
                 generics.where_clause.tail_span_for_suggestion(),
                 &msg_restrict_type_further,
@@ -262,6 +329,58 @@ pub fn suggest_constraining_type_param(
     }
 }
 
+/// Suggest restricting a return-position `impl Trait` with a new bound: `impl Foo` becomes
+/// `impl Foo + Bar`. Unlike a type parameter, it has no `hir::GenericParam` to add the bound to.
+pub fn suggest_constraining_opaque_type(
+    tcx: TyCtxt<'_>,
+    err: &mut DiagnosticBuilder<'_>,
+    def_id: DefId,
+    constraint: &str,
+) -> bool {
+    let (bounds, fn_def_id) = match tcx.hir().get_if_local(def_id) {
+        Some(hir::Node::Item(hir::Item {
+            kind:
+                hir::ItemKind::OpaqueTy(hir::OpaqueTy {
+                    bounds,
+                    impl_trait_fn: Some(fn_def_id),
+                    origin: hir::OpaqueTyOrigin::FnReturn,
+                    ..
+                }),
+            ..
+        })) => (bounds, *fn_def_id),
+        _ => return false,
+    };
+    let last_bound = match bounds.last() {
+        Some(bound) => bound,
+        None => return false,
+    };
+    // `impl Fn() -> A + Bar` would make `Bar` part of the return type of `Fn`.
+    if let hir::GenericBound::Trait(poly_trait_ref, _) = last_bound {
+        if poly_trait_ref
+            .trait_ref
+            .path
+            .segments
+            .last()
+            .and_then(|segment| segment.args)
+            .map_or(false, |args| args.parenthesized)
+        {
+            return false;
+        }
+    }
+
+    // The function's body has to satisfy the new bound too, so this may not be enough.
+    err.span_suggestion_verbose(
+        last_bound.span().shrink_to_hi(),
+        &format!(
+            "consider further restricting the `impl Trait` returned by `{}`",
+            tcx.def_path_str(fn_def_id)
+        ),
+        format!(" + {}", constraint),
+        Applicability::MaybeIncorrect,
+    );
+    true
+}
+
 /// Collect al types that have an implicit `'static` obligation that we could suggest `'_` for.
 pub struct TraitObjectVisitor<'tcx>(pub Vec<&'tcx hir::Ty<'tcx>>, pub crate::hir::map::Map<'tcx>);
 
@@ -3,6 +3,7 @@
 use rustc_errors::{Applicability, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::Visitor;
 use rustc_hir::{AsyncGeneratorKind, GeneratorKind};
 use rustc_index::vec::Idx;
 use rustc_middle::mir::{
@@ -10,9 +11,11 @@
     FakeReadCause, Local, LocalDecl, LocalInfo, LocalKind, Location, Operand, Place, PlaceRef,
     ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind, VarBindingForm,
 };
-use rustc_middle::ty::{self, suggest_constraining_type_param, Ty};
+use rustc_middle::ty::{self, suggest_constraining_type_param, Ty, TypeFoldable};
 use rustc_span::source_map::DesugaringKind;
+use rustc_span::symbol::{kw, sym};
 use rustc_span::Span;
+use rustc_trait_selection::traits::error_reporting::suggestions::ReturnsVisitor;
 
 use crate::dataflow::drop_flag_effects;
 use crate::dataflow::indexes::{MoveOutIndex, MovePathIndex};
@@ -903,7 +906,8 @@ pub(in crate::borrow_check) fn report_borrowed_value_does_not_live_long_enough(
                     region_name,
                     category,
                     span,
-                    &format!("`{}`", name),
+                    name,
+                    borrow.borrowed_place.ty(self.body, self.infcx.tcx).ty,
                 ),
             (
                 ref name,
@@ -1271,9 +1275,133 @@ fn try_report_cannot_return_reference_to_local(
             err.span_label(borrow_span, note);
         }
 
+        if let ConstraintCategory::Return(_) = category {
+            self.suggest_returning_owned_value(&mut err);
+        }
+
         Some(err)
     }
 
+    /// When a function returns a reference to data it owns, suggest returning an owned copy of
+    /// that data instead, changing both the return type and every returned value:
+    /// `fn name() -> &str { &s[1..] }` → `fn name() -> String { s[1..].to_owned() }`. A borrowed
+    /// local that already has the owned type is moved out instead: `&s` → `s`.
+    fn suggest_returning_owned_value(&self, err: &mut DiagnosticBuilder<'_>) {
+        let tcx = self.infcx.tcx;
+        let hir = tcx.hir();
+        let def_id = self.mir_def_id();
+        // The return type of closures and generators is inferred, so there is nothing to change.
+        if tcx.is_closure(def_id.to_def_id()) {
+            return;
+        }
+        let hir_id = self.mir_hir_id();
+        let ret_ty = match hir.fn_decl_by_hir_id(hir_id).map(|decl| &decl.output) {
+            Some(hir::FnRetTy::Return(ret_ty)) if !ret_ty.span.from_expansion() => ret_ty,
+            _ => return,
+        };
+        let ret_pointee = match ret_ty.kind {
+            hir::TyKind::Rptr(_, hir::MutTy { ty, mutbl: hir::Mutability::Not }) => ty,
+            _ => return,
+        };
+        let pointee = match *tcx.fn_sig(def_id).output().skip_binder().kind() {
+            ty::Ref(_, pointee, hir::Mutability::Not) => tcx.erase_regions(pointee),
+            _ => return,
+        };
+        if pointee.has_escaping_bound_vars() {
+            return;
+        }
+        let source_map = tcx.sess.source_map();
+        let snippet = |span| source_map.span_to_snippet(span).ok();
+        let (owned_ty, method) = match (pointee.kind(), &ret_pointee.kind) {
+            (ty::Str, _) => ("String".to_string(), "to_owned"),
+            (ty::Slice(elem), hir::TyKind::Slice(elem_ty)) if self.implements_clone(*elem) => {
+                match snippet(elem_ty.span) {
+                    Some(elem_ty) => (format!("Vec<{}>", elem_ty), "to_owned"),
+                    None => return,
+                }
+            }
+            // A `Copy` value is better returned as is than cloned.
+            _ if self.implements_clone(pointee)
+                && !self.infcx.type_is_copy_modulo_regions(
+                    self.param_env,
+                    pointee,
+                    ret_ty.span,
+                ) =>
+            {
+                match snippet(ret_pointee.span) {
+                    Some(pointee) => (pointee, "clone"),
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+
+        let body = hir.body(hir.body_owned_by(hir_id));
+        let mut visitor = ReturnsVisitor::default();
+        visitor.visit_body(body);
+        let typeck_results = tcx.typeck(def_id);
+        let mut parts = vec![(ret_ty.span, owned_ty)];
+        for ret in visitor.returns {
+            if ret.span.from_expansion() {
+                return;
+            }
+            let (value, borrowed_ty) = match ret.kind {
+                hir::ExprKind::AddrOf(hir::BorrowKind::Ref, hir::Mutability::Not, value) => {
+                    (value, typeck_results.expr_ty(value))
+                }
+                _ => match *typeck_results.expr_ty(ret).kind() {
+                    ty::Ref(_, ty, _) => (ret, ty),
+                    _ => return,
+                },
+            };
+            // Calling `method` on the value has to produce the new return type.
+            let borrowed_ty = tcx.erase_regions(borrowed_ty);
+            let becomes_owned_ty = borrowed_ty == pointee
+                || match (pointee.kind(), borrowed_ty.kind()) {
+                    (ty::Str, ty::Adt(def, _)) => tcx.is_diagnostic_item(sym::string_type, def.did),
+                    (ty::Slice(elem), ty::Adt(def, substs)) => {
+                        tcx.is_diagnostic_item(sym::vec_type, def.did) && substs.type_at(0) == *elem
+                    }
+                    _ => false,
+                };
+            if !becomes_owned_ty {
+                return;
+            }
+            // A borrowed local that already has the new return type can be returned as is.
+            let borrows_owned_local = ret.hir_id != value.hir_id
+                && (method == "clone" || borrowed_ty != pointee)
+                && matches!(
+                    value.kind,
+                    hir::ExprKind::Path(hir::QPath::Resolved(
+                        None,
+                        hir::Path { res: hir::def::Res::Local(_), .. }
+                    ))
+                );
+            // Method calls bind tighter than operators, casts and borrows.
+            let needs_parens = !matches!(
+                value.kind,
+                hir::ExprKind::Path(..)
+                    | hir::ExprKind::Field(..)
+                    | hir::ExprKind::Index(..)
+                    | hir::ExprKind::Call(..)
+                    | hir::ExprKind::MethodCall(..)
+                    | hir::ExprKind::Lit(..)
+            );
+            let owned_value = match snippet(value.span) {
+                Some(value) if borrows_owned_local => value,
+                Some(value) if needs_parens => format!("({}).{}()", value, method),
+                Some(value) => format!("{}.{}()", value, method),
+                None => return,
+            };
+            parts.push((ret.span, owned_value));
+        }
+        err.multipart_suggestion(
+            "consider returning an owned value instead of a reference",
+            parts,
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn report_escaping_closure_capture(
         &mut self,
         use_span: UseSpans<'tcx>,
@@ -1281,10 +1409,12 @@ fn report_escaping_closure_capture(
         fr_name: &RegionName,
         category: ConstraintCategory,
         constraint_span: Span,
-        captured_var: &str,
+        captured_name: &str,
+        captured_ty: Ty<'tcx>,
     ) -> DiagnosticBuilder<'cx> {
         let tcx = self.infcx.tcx;
         let args_span = use_span.args_or_use();
+        let captured_var = &format!("`{}`", captured_name);
 
         let suggestion = match tcx.sess.source_map().span_to_snippet(args_span) {
             Ok(mut string) => {
@@ -1339,9 +1469,64 @@ fn report_escaping_closure_capture(
             ),
         };
         err.span_note(constraint_span, &msg);
+
+        // Moving a reference into the closure doesn't make the data it points to live any
+        // longer, so for a `'static` requirement the closure needs to own a copy of that data.
+        // Every layer of references is peeled, since owning a copy of a `&&str` only gives a
+        // `&str`.
+        let mut pointee = captured_ty;
+        let mut derefs = 0;
+        while let ty::Ref(_, ty, _) = *pointee.kind() {
+            pointee = ty;
+            derefs += 1;
+        }
+        let to_owned = || {
+            if derefs > 1 {
+                format!("({}{}).to_owned()", "*".repeat(derefs - 1), captured_name)
+            } else {
+                format!("{}.to_owned()", captured_name)
+            }
+        };
+        // A copy of data that borrows something itself would still borrow it.
+        let owned = match *pointee.kind() {
+            _ if derefs == 0 || pointee.has_free_regions() => None,
+            // `to_string` goes through every layer of references on its own.
+            ty::Str => Some(format!("{}.to_string()", captured_name)),
+            ty::Slice(elem) if self.implements_clone(elem) => Some(to_owned()),
+            ty::Slice(_) => None,
+            _ if self.implements_clone(pointee) => Some(to_owned()),
+            _ => None,
+        };
+        if let Some(owned) = owned {
+            if fr_name.name == kw::StaticLifetime && captured_name != "self" {
+                err.help(&format!(
+                    "{var} is a reference, so moving it into the {kind} still borrows the data it \
+                     points to; to capture an owned copy instead, write \
+                     `let {name} = {owned};` before the {kind} and use the `move` keyword",
+                    var = captured_var,
+                    kind = kind,
+                    name = captured_name,
+                    owned = owned,
+                ));
+            }
+        }
         err
     }
 
+    /// Whether `ty`, which mustn't have any regions left, implements `Clone`.
+    fn implements_clone(&self, ty: Ty<'tcx>) -> bool {
+        let tcx = self.infcx.tcx;
+        match tcx.lang_items().clone_trait() {
+            Some(clone_trait) => tcx.type_implements_trait((
+                clone_trait,
+                ty,
+                ty::List::empty(),
+                self.param_env,
+            )),
+            None => false,
+        }
+    }
+
     fn report_escaping_data(
         &mut self,
         borrow_span: Span,
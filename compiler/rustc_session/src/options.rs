@@ -1114,6 +1114,9 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         "show extended diagnostic help (default: no)"),
     terminal_width: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
         "set the current terminal width"),
+    terse_trait_suggestions: bool = (false, parse_bool, [TRACKED],
+        "replace the code suggestions of unsatisfied trait bound errors with short help \
+        messages, and leave out their explanatory notes (default: no)"),
     tune_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select processor to schedule for (`rustc --print target-cpus` for details)"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1134,6 +1137,9 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    trait_cause_tree: bool = (false, parse_bool, [TRACKED],
+        "list the requirements that led to an unsatisfied trait bound as a numbered tree \
+        (default: no)"),
     trap_unreachable: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "generate trap instructions for unreachable intrinsics (default: use target setting, usually yes)"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
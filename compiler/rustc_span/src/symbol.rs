@@ -128,6 +128,7 @@
         Arguments,
         C,
         CString,
+        Cell,
         Center,
         Clone,
         Copy,
@@ -145,6 +146,7 @@
         FormatSpec,
         Formatter,
         From,
+        FromIterator,
         Future,
         FxHashMap,
         FxHashSet,
@@ -157,11 +159,13 @@
         Input,
         IntoIterator,
         Is,
+        Item,
         ItemContext,
         Iterator,
         Layout,
         Left,
         LintPass,
+        MutexGuard,
         None,
         Ok,
         Option,
@@ -185,11 +189,15 @@
         RangeToInclusive,
         Rc,
         Ready,
+        RefCell,
+        RefCellRef,
         Result,
         Return,
         Right,
         RustcDecodable,
         RustcEncodable,
+        RwLock,
+        RwLockReadGuard,
         Send,
         Some,
         StructuralEq,
@@ -349,6 +357,7 @@
         cmse_nonsecure_entry,
         coerce_unsized,
         cold,
+        collect,
         column,
         compile_error,
         compiler_builtins,
@@ -580,6 +589,8 @@
         gt,
         half_open_range_patterns,
         hash,
+        hashmap_type,
+        hashset_type,
         hexagon_target_feature,
         hidden,
         homogeneous_aggregate,
@@ -632,6 +643,7 @@
         item,
         item_like_imports,
         iter,
+        iter_mut,
         keyword,
         kind,
         kreg,
@@ -723,6 +735,7 @@
         must_use,
         mut_ptr,
         mut_slice_ptr,
+        mutex_type,
         naked,
         naked_functions,
         name,
@@ -788,6 +801,7 @@
         options,
         or,
         or_patterns,
+        osstring_type,
         other,
         out,
         overlapping_marker_traits,
@@ -814,6 +828,8 @@
         pat2018,
         pat2021,
         path,
+        path_type,
+        pathbuf_type,
         pattern_parentheses,
         phantom_data,
         pin,
@@ -1230,6 +1246,7 @@
         variant_count,
         vec,
         vec_type,
+        vecdeque_type,
         version,
         vis,
         visible_private_types,
@@ -5,27 +5,32 @@
     ConstEvalFailure, EvaluationResult, FulfillmentError, FulfillmentErrorCode,
     MismatchedProjectionTypes, Obligation, ObligationCause, ObligationCauseCode,
     OnUnimplementedDirective, OnUnimplementedNote, OutputTypeParameterMismatch, Overflow,
-    PredicateObligation, SelectionContext, SelectionError, TraitNotObjectSafe,
+    PredicateObligation, SelectionContext, SelectionError, TraitExplanation, TraitExplanationKind,
+    TraitNotObjectSafe,
 };
 
 use crate::infer::error_reporting::{TyCategory, TypeAnnotationNeeded as ErrorCode};
 use crate::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
 use crate::infer::{self, InferCtxt, TyCtxtInferExt};
-use rustc_data_structures::fx::FxHashMap;
-use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder, ErrorReported};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::{
+    pluralize, struct_span_err, Applicability, DiagnosticBuilder, ErrorReported, Level,
+};
 use rustc_hir as hir;
+use rustc_hir::def::Res;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_hir::intravisit::Visitor;
 use rustc_hir::Node;
 use rustc_middle::mir::interpret::ErrorHandled;
 use rustc_middle::ty::error::ExpectedFound;
 use rustc_middle::ty::fold::TypeFolder;
+use rustc_middle::ty::subst::Subst;
 use rustc_middle::ty::{
     self, fast_reject, AdtKind, SubtypePredicate, ToPolyTraitRef, ToPredicate, Ty, TyCtxt,
     TypeFoldable, WithConstness,
 };
 use rustc_session::DiagnosticMessageId;
-use rustc_span::symbol::{kw, sym};
+use rustc_span::symbol::{kw, sym, Symbol};
 use rustc_span::{ExpnKind, MultiSpan, Span, DUMMY_SP};
 use std::fmt;
 
@@ -62,10 +67,27 @@ fn report_selection_error(
         points_at_arg: bool,
     );
 
+    /// Best-effort scrape of the primary message, labels, notes and suggestions that reporting
+    /// the unmet trait `obligation` would produce, for tools that embed the compiler and present
+    /// these on their own. Nothing is reported: the usual suggestion machinery is run on a
+    /// diagnostic that is never emitted, and whatever ended up on it is read back out. Inference
+    /// variables created along the way are rolled back.
+    ///
+    /// This isn't exactly what `report_selection_error` emits: the errors that aren't about an
+    /// unmet trait bound, like closure kind or signature mismatches, and the rewording specific
+    /// to `?` aren't covered. `points_at_arg` is what a caller of `report_selection_error` would
+    /// pass, and enables the suggestions that edit the call argument `obligation` points at.
+    fn scrape_trait_obligation_explanations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        points_at_arg: bool,
+    ) -> Vec<TraitExplanation<'tcx>>;
+
     /// Given some node representing a fn-like thing in the HIR map,
     /// returns a span and `ArgKind` information that describes the
     /// arguments it expects. This can be supplied to
-    /// `report_arg_count_mismatch`.
+    /// `report_arg_count_mismatch`. Returns `None` if the node isn't
+    /// fn-like.
     fn get_fn_like_arguments(&self, node: Node<'_>) -> Option<(Span, Vec<ArgKind>)>;
 
     /// Reports an error when the number of arguments needed by a
@@ -78,6 +100,7 @@ fn report_arg_count_mismatch(
         expected_args: Vec<ArgKind>,
         found_args: Vec<ArgKind>,
         is_closure: bool,
+        found_body: Option<hir::BodyId>,
     ) -> DiagnosticBuilder<'tcx>;
 }
 
@@ -198,9 +221,11 @@ fn report_overflow_error<T>(
         self.note_obligation_cause_code(
             &mut err,
             &obligation.predicate,
+            &obligation.cause,
             &obligation.cause.code,
             &mut vec![],
             &mut Default::default(),
+            None,
         );
 
         err.emit();
@@ -245,14 +270,25 @@ fn report_selection_error(
                     trait_item_def_id,
                 } = obligation.cause.code
                 {
-                    self.report_extra_impl_obligation(
+                    let mut err = self.report_extra_impl_obligation(
                         span,
                         item_name,
                         impl_item_def_id,
                         trait_item_def_id,
                         &format!("`{}`", obligation.predicate),
-                    )
-                    .emit();
+                    );
+                    if let ObligationCauseCode::CompareImplMethodObligation { .. } =
+                        obligation.cause.code
+                    {
+                        self.suggest_moving_impl_method_where_bound(
+                            &mut err,
+                            obligation.predicate,
+                            impl_item_def_id,
+                            trait_item_def_id,
+                        );
+                    }
+                    self.make_suggestions_terse(&mut err);
+                    err.emit();
                     return;
                 }
 
@@ -368,14 +404,19 @@ fn report_selection_error(
                                 )
                             };
 
+                        // Only the generic primary message gets replaced when suggesting to
+                        // borrow the argument, a custom one or the one for `?` is kept and the
+                        // original obligation is mentioned in a note instead.
                         if self.suggest_add_reference_to_arg(
                             &obligation,
                             &mut err,
                             &trait_ref,
                             points_at_arg,
-                            have_alt_message,
+                            have_alt_message || is_try && is_from,
                         ) {
+                            self.suggest_derive(&obligation, &mut err, trait_ref);
                             self.note_obligation_cause(&mut err, obligation);
+                            self.make_suggestions_terse(&mut err);
                             err.emit();
                             return;
                         }
@@ -415,17 +456,16 @@ fn report_selection_error(
                             err.span_label(enclosing_scope_span, s.as_str());
                         }
 
-                        self.suggest_dereferences(&obligation, &mut err, trait_ref, points_at_arg);
-                        self.suggest_fn_call(&obligation, &mut err, trait_ref, points_at_arg);
-                        self.suggest_remove_reference(&obligation, &mut err, trait_ref);
-                        self.suggest_semicolon_removal(&obligation, &mut err, span, trait_ref);
-                        self.note_version_mismatch(&mut err, &trait_ref);
-
-                        if Some(trait_ref.def_id()) == tcx.lang_items().try_trait() {
-                            self.suggest_await_before_try(&mut err, &obligation, trait_ref, span);
-                        }
+                        let restricted_param = self.suggest_for_unmet_trait_bound(
+                            &obligation,
+                            &mut err,
+                            trait_ref,
+                            points_at_arg,
+                            have_alt_message,
+                        );
 
                         if self.suggest_impl_trait(&mut err, span, &obligation, trait_ref) {
+                            self.make_suggestions_terse(&mut err);
                             err.emit();
                             return;
                         }
@@ -460,8 +500,25 @@ fn report_selection_error(
                             );
                         }
 
-                        // Try to report a help message
-                        if !trait_ref.has_infer_types_or_consts()
+                        // Try to report a help message. A returned `impl Trait` never meets
+                        // the bound from here, but it can be made to by adding it. That doesn't
+                        // apply to `main`, whose return type only has to be `Termination`.
+                        let is_opaque =
+                            matches!(trait_ref.skip_binder().self_ty().kind(), ty::Opaque(..))
+                                && obligation.cause.code != ObligationCauseCode::MainFunctionType;
+                        let constrained_opaque =
+                            is_opaque && !trait_ref.has_infer_types_or_consts() && {
+                                let suggestions_len = err.suggestions.len();
+                                self.suggest_restricting_param_bound(
+                                    &mut err,
+                                    trait_ref,
+                                    &obligation,
+                                );
+                                err.suggestions.len() > suggestions_len
+                            };
+                        if constrained_opaque {
+                            // Adding the bound to the `impl Trait` is all that's needed.
+                        } else if !trait_ref.has_infer_types_or_consts()
                             && self.predicate_can_apply(obligation.param_env, trait_ref)
                         {
                             // If a where-clause may be useful, remind the
@@ -471,11 +528,13 @@ fn report_selection_error(
                             // these notes will often be of the form
                             //     "the type `T` can't be frobnicated"
                             // which is somewhat confusing.
-                            self.suggest_restricting_param_bound(
-                                &mut err,
-                                trait_ref,
-                                obligation.cause.body_id,
-                            );
+                            if !restricted_param {
+                                self.suggest_restricting_param_bound(
+                                    &mut err,
+                                    trait_ref,
+                                    &obligation,
+                                );
+                            }
                         } else {
                             if !have_alt_message {
                                 // Can't show anything else useful, try to find similar impls.
@@ -490,8 +549,10 @@ fn report_selection_error(
                                     &mut err,
                                     trait_ref,
                                     points_at_arg,
+                                    have_alt_message,
                                 );
                             }
+                            self.suggest_dyn_trait_parameter(&obligation, &mut err, trait_ref);
                         }
 
                         // If this error is due to `!: Trait` not implemented but `(): Trait` is
@@ -621,6 +682,7 @@ fn report_selection_error(
                             }
                         }
 
+                        self.make_suggestions_terse(&mut err);
                         err.emit();
                         return;
                     }
@@ -732,6 +794,10 @@ fn report_selection_error(
                             Some((Some(found_span), found))
                         })
                         .unwrap_or((found_span, found));
+                    let found_body = found_did
+                        .and_then(|did| did.as_local())
+                        .map(|did| self.tcx.hir().local_def_id_to_hir_id(did))
+                        .and_then(|hir_id| self.tcx.hir().maybe_body_owned_by(hir_id));
 
                     self.report_arg_count_mismatch(
                         span,
@@ -739,6 +805,7 @@ fn report_selection_error(
                         expected,
                         found,
                         found_trait_ty.is_closure(),
+                        found_body,
                     )
                 }
             }
@@ -772,17 +839,45 @@ fn report_selection_error(
 
         self.note_obligation_cause(&mut err, obligation);
         self.point_at_returns_when_relevant(&mut err, &obligation);
+        self.make_suggestions_terse(&mut err);
 
         err.emit();
     }
 
+    fn scrape_trait_obligation_explanations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        points_at_arg: bool,
+    ) -> Vec<TraitExplanation<'tcx>> {
+        let bound_predicate = obligation.predicate.bound_atom();
+        let trait_ref = match bound_predicate.skip_binder() {
+            ty::PredicateAtom::Trait(trait_predicate, _) => self
+                .resolve_vars_if_possible(bound_predicate.rebind(trait_predicate))
+                .to_poly_trait_ref(),
+            _ => return vec![],
+        };
+        // Suggestions that are only made once shouldn't be used up by a diagnostic that is never
+        // emitted.
+        let one_time_diagnostics = self.tcx.sess.one_time_diagnostics.borrow().clone();
+        let explanations =
+            self.probe(|_| self.scrape_trait_explanations(obligation, trait_ref, points_at_arg));
+        *self.tcx.sess.one_time_diagnostics.borrow_mut() = one_time_diagnostics;
+        explanations
+    }
+
     /// Given some node representing a fn-like thing in the HIR map,
     /// returns a span and `ArgKind` information that describes the
     /// arguments it expects. This can be supplied to
-    /// `report_arg_count_mismatch`.
+    /// `report_arg_count_mismatch`. Returns `None` if the node isn't
+    /// fn-like.
     fn get_fn_like_arguments(&self, node: Node<'_>) -> Option<(Span, Vec<ArgKind>)> {
         let sm = self.tcx.sess.source_map();
         let hir = self.tcx.hir();
+        // The snippet might not be available, for example for patterns coming from a macro
+        // defined in another crate. Still describe the argument rather than giving up on it, so
+        // that we don't claim that the closure takes fewer arguments than it does.
+        let pat_name =
+            |pat: &hir::Pat<'_>| sm.span_to_snippet(pat.span).unwrap_or_else(|_| "_".to_owned());
         Some(match node {
             Node::Expr(&hir::Expr {
                 kind: hir::ExprKind::Closure(_, ref _decl, id, span, _),
@@ -796,22 +891,15 @@ fn get_fn_like_arguments(&self, node: Node<'_>) -> Option<(Span, Vec<ArgKind>)>
                         if let hir::Pat { kind: hir::PatKind::Tuple(ref args, _), span, .. } =
                             *arg.pat
                         {
-                            Some(ArgKind::Tuple(
+                            ArgKind::Tuple(
                                 Some(span),
-                                args.iter()
-                                    .map(|pat| {
-                                        sm.span_to_snippet(pat.span)
-                                            .ok()
-                                            .map(|snippet| (snippet, "_".to_owned()))
-                                    })
-                                    .collect::<Option<Vec<_>>>()?,
-                            ))
+                                args.iter().map(|pat| (pat_name(pat), "_".to_owned())).collect(),
+                            )
                         } else {
-                            let name = sm.span_to_snippet(arg.pat.span).ok()?;
-                            Some(ArgKind::Arg(name, "_".to_owned()))
+                            ArgKind::Arg(pat_name(arg.pat), "_".to_owned())
                         }
                     })
-                    .collect::<Option<Vec<ArgKind>>>()?,
+                    .collect::<Vec<ArgKind>>(),
             ),
             Node::Item(&hir::Item { span, kind: hir::ItemKind::Fn(ref sig, ..), .. })
             | Node::ImplItem(&hir::ImplItem {
@@ -824,11 +912,22 @@ fn get_fn_like_arguments(&self, node: Node<'_>) -> Option<(Span, Vec<ArgKind>)>
                 kind: hir::TraitItemKind::Fn(ref sig, _),
                 ..
             }) => (
-                sm.guess_head_span(span),
+                match sig.header.asyncness {
+                    // The body of an `async fn` is lowered into a generator, point at the
+                    // signature the user wrote instead of guessing where the head ends.
+                    hir::IsAsync::Async => sig.span,
+                    hir::IsAsync::NotAsync => sm.guess_head_span(span),
+                },
                 sig.decl
                     .inputs
                     .iter()
-                    .map(|arg| match arg.clone().kind {
+                    .enumerate()
+                    .map(|(i, arg)| match arg.clone().kind {
+                        // The `self` receiver is still passed as the first argument when the
+                        // method is used as a function, name it to be able to point it out.
+                        _ if i == 0 && sig.decl.implicit_self.has_implicit_self() => {
+                            ArgKind::Arg(kw::SelfLower.to_string(), "_".to_owned())
+                        }
                         hir::TyKind::Tup(ref tys) => ArgKind::Tuple(
                             Some(arg.span),
                             vec![("_".to_owned(), "_".to_owned()); tys.len()],
@@ -842,7 +941,12 @@ fn get_fn_like_arguments(&self, node: Node<'_>) -> Option<(Span, Vec<ArgKind>)>
                 let span = sm.guess_head_span(span);
                 (span, vec![ArgKind::empty(); variant_data.fields().len()])
             }
-            _ => panic!("non-FnLike node found: {:?}", node),
+            // Not something we know how to describe the arguments of; let the caller fall back to
+            // a less specific message instead of crashing while reporting an error.
+            _ => {
+                debug!("get_fn_like_arguments: non-FnLike node found: {:?}", node);
+                return None;
+            }
         })
     }
 
@@ -856,6 +960,7 @@ fn report_arg_count_mismatch(
         expected_args: Vec<ArgKind>,
         found_args: Vec<ArgKind>,
         is_closure: bool,
+        found_body: Option<hir::BodyId>,
     ) -> DiagnosticBuilder<'tcx> {
         let kind = if is_closure { "closure" } else { "function" };
 
@@ -891,7 +996,16 @@ fn report_arg_count_mismatch(
         err.span_label(span, format!("expected {} that takes {}", kind, expected_str));
 
         if let Some(found_span) = found_span {
-            err.span_label(found_span, format!("takes {}", found_str));
+            let takes_self = !is_closure
+                && matches!(found_args.first(), Some(ArgKind::Arg(name, _)) if name == "self");
+            if takes_self {
+                err.span_label(
+                    found_span,
+                    format!("takes {}, including the `self` receiver", found_str),
+                );
+            } else {
+                err.span_label(found_span, format!("takes {}", found_str));
+            }
 
             // move |_| { ... }
             // ^^^^^^^^-- def_span
@@ -935,6 +1049,25 @@ fn report_arg_count_mismatch(
                     );
                 }
             }
+            // |pair| pair.0 + pair.1
+            // ^^^^^^-- the closure only uses its argument through the fields of the expected
+            //          arguments, so those uses need to be rewritten along with the arguments.
+            if let (&[ArgKind::Arg(..)], Some(body_id)) = (&found_args[..], found_body) {
+                if is_closure && expected_args.len() > 1 {
+                    if let Some((names, uses)) =
+                        self.tuple_field_uses_of_single_arg(body_id, expected_args.len())
+                    {
+                        let mut parts = vec![(pipe_span, format!("|{}|", names.join(", ")))];
+                        parts.extend(uses.into_iter().map(|(span, i)| (span, names[i].clone())));
+                        err.multipart_suggestion(
+                            "change the closure to take multiple arguments instead of a single \
+                             tuple",
+                            parts,
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                }
+            }
             if let &[ArgKind::Tuple(_, ref fields)] = &expected_args[..] {
                 if fields.len() == found_args.len() && is_closure {
                     let sugg = format!(
@@ -943,6 +1076,13 @@ fn report_arg_count_mismatch(
                             .iter()
                             .map(|arg| match arg {
                                 ArgKind::Arg(name, _) => name.to_owned(),
+                                // Keep nested patterns, the closure body might use their bindings.
+                                ArgKind::Tuple(Some(span), _) => self
+                                    .tcx
+                                    .sess
+                                    .source_map()
+                                    .span_to_snippet(*span)
+                                    .unwrap_or_else(|_| "_".to_owned()),
                                 _ => "_".to_owned(),
                             })
                             .collect::<Vec<String>>()
@@ -1000,6 +1140,16 @@ fn report_projection_error(
 
     fn describe_generator(&self, body_id: hir::BodyId) -> Option<&'static str>;
 
+    /// If the closure `body_id` takes a single argument that is only ever used through its
+    /// fields `.0` to `.{arity - 1}`, returns names for the arguments that would replace it, one
+    /// per field, and the spans of those field accesses along with their index. The names are
+    /// the argument's name suffixed with the index, and must not already be used in the body.
+    fn tuple_field_uses_of_single_arg(
+        &self,
+        body_id: hir::BodyId,
+        arity: usize,
+    ) -> Option<(Vec<String>, Vec<(Span, usize)>)>;
+
     fn find_similar_impl_candidates(
         &self,
         trait_ref: ty::PolyTraitRef<'tcx>,
@@ -1026,6 +1176,39 @@ fn note_version_mismatch(
         trait_ref: &ty::PolyTraitRef<'tcx>,
     );
 
+    /// If the unmet `obligation` comes from the where clauses of a specializing impl, notes that
+    /// the more general impl would have applied.
+    fn note_shadowed_blanket_impl(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    );
+
+    /// The body of `scrape_trait_obligation_explanations`, which is run in a probe.
+    fn scrape_trait_explanations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        points_at_arg: bool,
+    ) -> Vec<TraitExplanation<'tcx>>;
+
+    /// Add the notes and suggestions for the unmet trait bound `trait_ref` that don't depend on
+    /// how the error itself is worded. Returns whether restricting the type parameter that doesn't
+    /// meet the bound was already suggested.
+    fn suggest_for_unmet_trait_bound(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        points_at_arg: bool,
+        has_custom_message: bool,
+    ) -> bool;
+
+    /// With `-Z terse-trait-suggestions`, replace the code suggestions made while reporting an
+    /// error with a short help message each. The suggested code is kept in the message when it
+    /// fits on a single line.
+    fn make_suggestions_terse(&self, err: &mut DiagnosticBuilder<'_>);
+
     /// Creates a `PredicateObligation` with `new_self_ty` replacing the existing type in the
     /// `trait_ref`.
     ///
@@ -1063,7 +1246,7 @@ fn suggest_unsized_bound_if_applicable(
 
     fn is_recursive_obligation(
         &self,
-        obligated_types: &mut Vec<&ty::TyS<'tcx>>,
+        obligated_predicates: &mut Vec<ty::Predicate<'tcx>>,
         cause_code: &ObligationCauseCode<'tcx>,
     ) -> bool;
 }
@@ -1230,6 +1413,13 @@ fn report_projection_error(
                     predicate
                 );
                 self.note_type_err(&mut diag, &obligation.cause, None, values, err);
+                if let ty::PredicateAtom::Projection(data) = bound_predicate.skip_binder() {
+                    self.suggest_flatten_nested_collection(
+                        &mut diag,
+                        obligation,
+                        bound_predicate.rebind(data),
+                    );
+                }
                 self.note_obligation_cause(&mut diag, obligation);
                 diag.emit();
             }
@@ -1287,6 +1477,45 @@ fn describe_generator(&self, body_id: hir::BodyId) -> Option<&'static str> {
         })
     }
 
+    fn tuple_field_uses_of_single_arg(
+        &self,
+        body_id: hir::BodyId,
+        arity: usize,
+    ) -> Option<(Vec<String>, Vec<(Span, usize)>)> {
+        let hir = self.tcx.hir();
+        let body = hir.body(body_id);
+        let (binding, name) = match body.params {
+            [param] => match param.pat.kind {
+                hir::PatKind::Binding(hir::BindingAnnotation::Unannotated, hir_id, ident, None) => {
+                    (hir_id, ident.name)
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let mut visitor = TupleFieldUsesVisitor {
+            hir_map: hir,
+            binding,
+            fields: vec![],
+            other_use: false,
+            names: Default::default(),
+        };
+        visitor.visit_body(body);
+        if visitor.other_use
+            || visitor.fields.is_empty()
+            || visitor.fields.iter().any(|&(span, i)| i >= arity || span.from_expansion())
+        {
+            return None;
+        }
+        // A name that is already used in the body would either shadow what it refers to, or be
+        // shadowed by the binding that introduces it.
+        let names = (0..arity).map(|i| format!("{}_{}", name, i)).collect::<Vec<_>>();
+        if names.iter().any(|name| visitor.names.contains(&Symbol::intern(name))) {
+            return None;
+        }
+        Some((names, visitor.fields))
+    }
+
     fn find_similar_impl_candidates(
         &self,
         trait_ref: ty::PolyTraitRef<'tcx>,
@@ -1416,6 +1645,198 @@ fn note_version_mismatch(
         }
     }
 
+    fn scrape_trait_explanations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        points_at_arg: bool,
+    ) -> Vec<TraitExplanation<'tcx>> {
+        // Collect everything on a diagnostic that is never emitted.
+        let mut err = self.tcx.sess.diagnostic().struct_dummy();
+        let OnUnimplementedNote { message, label, .. } =
+            self.on_unimplemented_note(trait_ref, obligation);
+        let have_alt_message = message.is_some() || label.is_some();
+        err.set_primary_message(message.unwrap_or_else(|| {
+            format!(
+                "the trait bound `{}` is not satisfied",
+                trait_ref.without_const().to_predicate(self.tcx),
+            )
+        }));
+        // Like `report_selection_error`, stop at the borrow suggestion when there is one.
+        if !self.suggest_add_reference_to_arg(
+            obligation,
+            &mut err,
+            &trait_ref,
+            points_at_arg,
+            have_alt_message,
+        ) {
+            self.suggest_for_unmet_trait_bound(
+                obligation,
+                &mut err,
+                trait_ref,
+                points_at_arg,
+                have_alt_message,
+            );
+        }
+        self.note_obligation_cause(&mut err, obligation);
+
+        let explanation = |kind, message, spans, edits| TraitExplanation {
+            cause_code: obligation.cause.code.clone(),
+            kind,
+            message,
+            spans,
+            edits,
+        };
+        let mut explanations =
+            vec![explanation(TraitExplanationKind::Message, err.message(), vec![], vec![])];
+        explanations.extend(err.span.span_labels().into_iter().filter_map(|label| {
+            Some(explanation(TraitExplanationKind::Label, label.label?, vec![label.span], vec![]))
+        }));
+        explanations.extend(err.children.iter().map(|child| {
+            let kind = match child.level {
+                Level::Help => TraitExplanationKind::Help,
+                _ => TraitExplanationKind::Note,
+            };
+            explanation(kind, child.message(), child.span.primary_spans().to_vec(), vec![])
+        }));
+        explanations.extend(err.suggestions.iter().map(|suggestion| {
+            let edits = suggestion.substitutions.first().map_or(vec![], |substitution| {
+                substitution.parts.iter().map(|part| (part.span, part.snippet.clone())).collect()
+            });
+            explanation(TraitExplanationKind::Suggestion, suggestion.msg.clone(), vec![], edits)
+        }));
+        err.cancel();
+        explanations
+    }
+
+    fn suggest_for_unmet_trait_bound(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        points_at_arg: bool,
+        has_custom_message: bool,
+    ) -> bool {
+        let span = obligation.cause.span;
+        self.suggest_dereferences(obligation, err, trait_ref, points_at_arg);
+        self.suggest_fn_call(obligation, err, trait_ref, points_at_arg);
+        self.suggest_closure_as_fn_ptr(obligation, err, trait_ref);
+        self.suggest_path_string_conversion(obligation, err, trait_ref, points_at_arg);
+        self.suggest_cloned_instead_of_clone(obligation, err, trait_ref);
+        self.suggest_into_iter_instead_of_iter(obligation, err, trait_ref);
+        self.suggest_as_ref_for_option_or_result(obligation, err, trait_ref, points_at_arg);
+        self.suggest_slice_or_vec_for_array(obligation, err, trait_ref, points_at_arg);
+        self.suggest_question_mark_on_result(obligation, err, trait_ref, points_at_arg);
+        self.suggest_pinning(obligation, err, trait_ref, points_at_arg);
+        self.suggest_accessing_guarded_value(obligation, err, trait_ref, points_at_arg);
+        self.suggest_accessing_field(obligation, err, trait_ref, points_at_arg);
+        self.suggest_borrowing_index(obligation, err, trait_ref);
+        self.suggest_swapping_type_params(obligation, err, trait_ref);
+        if !self.suggest_mut_reborrow(obligation, err, trait_ref, points_at_arg)
+            && !self.suggest_remove_reference(obligation, err, trait_ref, has_custom_message)
+        {
+            self.suggest_iteration_method(obligation, err, trait_ref);
+        }
+        self.suggest_semicolon_removal(obligation, err, span, trait_ref);
+        self.suggest_moving_unsized_tuple_elem(obligation, err, trait_ref);
+        let restricted_param = self.suggest_derive(obligation, err, trait_ref);
+        self.suggest_impl_for_local_type(err, trait_ref);
+        self.note_version_mismatch(err, &trait_ref);
+        self.note_shadowed_blanket_impl(obligation, err);
+
+        if Some(trait_ref.def_id()) == self.tcx.lang_items().try_trait() {
+            self.suggest_await_before_try(err, obligation, trait_ref, span);
+        }
+        restricted_param
+    }
+
+    fn make_suggestions_terse(&self, err: &mut DiagnosticBuilder<'_>) {
+        if !self.terse_suggestions() {
+            return;
+        }
+        let source_map = self.tcx.sess.source_map();
+        for suggestion in std::mem::take(&mut err.suggestions) {
+            let spliced = suggestion.splice_lines(source_map);
+            match spliced.first().map(|(snippet, ..)| snippet.trim()) {
+                Some(snippet) if !snippet.is_empty() && !snippet.contains('\n') => {
+                    err.help(&format!("{}: `{}`", suggestion.msg, snippet));
+                }
+                _ => {
+                    err.help(&suggestion.msg);
+                }
+            }
+        }
+    }
+
+    /// If `obligation` is a where clause of an impl that was selected because it specializes a
+    /// more general impl, and that more general impl would have applied, point out that the more
+    /// specific impl took precedence.
+    fn note_shadowed_blanket_impl(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let tcx = self.tcx;
+        let parent_trait_ref = match &obligation.cause.code {
+            ObligationCauseCode::ImplDerivedObligation(data) => {
+                self.resolve_vars_if_possible(data.parent_trait_ref)
+            }
+            _ => return,
+        };
+        let parent_trait_ref = match parent_trait_ref.no_bound_vars() {
+            Some(trait_ref) if !trait_ref.has_infer_types_or_consts() => trait_ref,
+            _ => return,
+        };
+
+        // Every impl whose header matches the parent trait ref, along with whether all of its
+        // where clauses may hold.
+        let mut matching_impls = vec![];
+        tcx.for_each_relevant_impl(parent_trait_ref.def_id, parent_trait_ref.self_ty(), |def_id| {
+            let bounds_may_hold = self.probe(|_| {
+                let impl_substs = self.fresh_substs_for_item(obligation.cause.span, def_id);
+                let impl_trait_ref = tcx.impl_trait_ref(def_id)?.subst(tcx, impl_substs);
+                self.at(&obligation.cause, obligation.param_env)
+                    .eq(impl_trait_ref, parent_trait_ref)
+                    .ok()?;
+                let predicates = tcx.predicates_of(def_id).instantiate(tcx, impl_substs);
+                Some(predicates.predicates.into_iter().all(|predicate| {
+                    self.predicate_may_hold(&Obligation::new(
+                        obligation.cause.clone(),
+                        obligation.param_env,
+                        predicate,
+                    ))
+                }))
+            });
+            if let Some(bounds_may_hold) = bounds_may_hold {
+                matching_impls.push((def_id, bounds_may_hold));
+            }
+        });
+
+        for &(specific, _) in matching_impls.iter().filter(|(_, may_hold)| !may_hold) {
+            let blanket = matching_impls.iter().find(|&&(blanket, may_hold)| {
+                may_hold && blanket != specific && tcx.specializes((specific, blanket))
+            });
+            if let Some(&(blanket, _)) = blanket {
+                let sm = tcx.sess.source_map();
+                err.span_note(
+                    sm.guess_head_span(tcx.def_span(specific)),
+                    &format!(
+                        "the impl of `{}` for `{}` takes precedence over the more general impl it \
+                         specializes, but it requires `{}`",
+                        parent_trait_ref.print_only_trait_path(),
+                        parent_trait_ref.self_ty(),
+                        obligation.predicate,
+                    ),
+                );
+                err.span_note(
+                    sm.guess_head_span(tcx.def_span(blanket)),
+                    "this more general impl would have applied",
+                );
+                return;
+            }
+        }
+    }
+
     fn mk_trait_obligation_with_new_self_ty(
         &self,
         param_env: ty::ParamEnv<'tcx>,
@@ -1505,15 +1926,29 @@ fn maybe_report_ambiguity(
                 // avoid inundating the user with unnecessary errors, but we now
                 // check upstream for type errors and don't add the obligations to
                 // begin with in those cases.
+                let self_ty = trait_ref.skip_binder().self_ty();
                 if self.tcx.lang_items().sized_trait() == Some(trait_ref.def_id()) {
-                    self.emit_inference_failure_err(body_id, span, subst, ErrorCode::E0282).emit();
+                    let mut err =
+                        self.emit_inference_failure_err(body_id, span, subst, ErrorCode::E0282);
+                    self.suggest_collect_target_type(&mut err, obligation, self_ty, body_id);
+                    err.emit();
                     return;
                 }
                 let mut err =
                     self.emit_inference_failure_err(body_id, span, subst, ErrorCode::E0283);
                 err.note(&format!("cannot satisfy `{}`", predicate));
+                self.suggest_add_type_annotation(&mut err, trait_ref, body_id);
+                if self.tcx.get_diagnostic_item(sym::FromIterator) == Some(trait_ref.def_id()) {
+                    self.suggest_collect_target_type(&mut err, obligation, self_ty, body_id);
+                }
                 if let ObligationCauseCode::ItemObligation(def_id) = obligation.cause.code {
-                    self.suggest_fully_qualified_path(&mut err, def_id, span, trait_ref.def_id());
+                    self.suggest_fully_qualified_path(
+                        &mut err,
+                        def_id,
+                        span,
+                        trait_ref.def_id(),
+                        obligation.cause.body_id,
+                    );
                 } else if let (
                     Ok(ref snippet),
                     ObligationCauseCode::BindingObligation(ref def_id, _),
@@ -1700,12 +2135,16 @@ fn note_obligation_cause(
         // First, attempt to add note to this error with an async-await-specific
         // message, and fall back to regular note otherwise.
         if !self.maybe_note_obligation_cause_for_async_await(err, obligation) {
+            let tree_depth =
+                if self.tcx.sess.opts.debugging_opts.trait_cause_tree { Some(0) } else { None };
             self.note_obligation_cause_code(
                 err,
                 &obligation.predicate,
+                &obligation.cause,
                 &obligation.cause.code,
                 &mut vec![],
                 &mut Default::default(),
+                tree_depth,
             );
             self.suggest_unsized_bound_if_applicable(err, obligation);
         }
@@ -1810,13 +2249,15 @@ fn suggest_unsized_bound_if_applicable(
 
     fn is_recursive_obligation(
         &self,
-        obligated_types: &mut Vec<&ty::TyS<'tcx>>,
+        obligated_predicates: &mut Vec<ty::Predicate<'tcx>>,
         cause_code: &ObligationCauseCode<'tcx>,
     ) -> bool {
-        if let ObligationCauseCode::BuiltinDerivedObligation(ref data) = cause_code {
+        if let ObligationCauseCode::BuiltinDerivedObligation(ref data)
+        | ObligationCauseCode::ImplDerivedObligation(ref data) = cause_code
+        {
             let parent_trait_ref = self.resolve_vars_if_possible(data.parent_trait_ref);
-
-            if obligated_types.iter().any(|ot| ot == &parent_trait_ref.skip_binder().self_ty()) {
+            let parent_predicate = parent_trait_ref.without_const().to_predicate(self.tcx);
+            if obligated_predicates.contains(&parent_predicate) {
                 return true;
             }
         }
@@ -1824,6 +2265,63 @@ fn is_recursive_obligation(
     }
 }
 
+/// Collect the tuple field accesses through a local binding, like `pair.0` and `pair.1` for
+/// `pair`, and whether the binding is also used in any other way, along with every name that is
+/// bound or referred to by a single-segment path.
+struct TupleFieldUsesVisitor<'hir> {
+    hir_map: rustc_middle::hir::map::Map<'hir>,
+    binding: hir::HirId,
+    fields: Vec<(Span, usize)>,
+    other_use: bool,
+    names: FxHashSet<Symbol>,
+}
+
+impl<'hir> TupleFieldUsesVisitor<'hir> {
+    fn is_binding(&self, ex: &hir::Expr<'_>) -> bool {
+        matches!(
+            ex.kind,
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path))
+                if path.res == Res::Local(self.binding)
+        )
+    }
+}
+
+impl<'hir> Visitor<'hir> for TupleFieldUsesVisitor<'hir> {
+    type Map = rustc_middle::hir::map::Map<'hir>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        // Also look into the closures nested in the body, they can capture the binding.
+        hir::intravisit::NestedVisitorMap::OnlyBodies(self.hir_map)
+    }
+
+    fn visit_expr(&mut self, ex: &'hir hir::Expr<'hir>) {
+        match ex.kind {
+            hir::ExprKind::Field(base, ident) if self.is_binding(base) => {
+                match ident.as_str().parse::<usize>() {
+                    Ok(i) => self.fields.push((ex.span, i)),
+                    Err(_) => self.other_use = true,
+                }
+            }
+            _ if self.is_binding(ex) => self.other_use = true,
+            _ => {
+                if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = ex.kind {
+                    if let [segment] = path.segments {
+                        self.names.insert(segment.ident.name);
+                    }
+                }
+                hir::intravisit::walk_expr(self, ex)
+            }
+        }
+    }
+
+    fn visit_pat(&mut self, pat: &'hir hir::Pat<'hir>) {
+        if let hir::PatKind::Binding(_, _, ident, _) = pat.kind {
+            self.names.insert(ident.name);
+        }
+        hir::intravisit::walk_pat(self, pat)
+    }
+}
+
 /// Look for type `param` in an ADT being used only through a reference to confirm that suggesting
 /// `param: ?Sized` would be a valid constraint.
 struct FindTypeParam {
@@ -7,29 +7,36 @@
 use crate::infer::InferCtxt;
 use crate::traits::normalize_projection_type;
 
+use rustc_ast::util::parser::PREC_POSTFIX;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::stack::ensure_sufficient_stack;
-use rustc_errors::{error_code, struct_span_err, Applicability, DiagnosticBuilder, Style};
+use rustc_errors::{
+    error_code, pluralize, struct_span_err, Applicability, DiagnosticBuilder, Level, Style,
+    SuggestionCategory,
+};
 use rustc_hir as hir;
-use rustc_hir::def::DefKind;
-use rustc_hir::def_id::DefId;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_hir::intravisit::Visitor;
 use rustc_hir::lang_items::LangItem;
 use rustc_hir::{AsyncGeneratorKind, GeneratorKind, Node};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::subst::{GenericArg, GenericArgKind, InternalSubsts, Subst};
 use rustc_middle::ty::{
-    self, suggest_constraining_type_param, AdtKind, DefIdTree, Infer, InferTy, ToPredicate, Ty,
-    TyCtxt, TypeFoldable, WithConstness,
+    self, suggest_constraining_opaque_type, suggest_constraining_type_params, AdtKind, DefIdTree,
+    Infer, InferTy, ToPredicate, Ty, TyCtxt, TypeFoldable, WithConstness,
 };
 use rustc_middle::ty::{TypeAndMut, TypeckResults};
+use rustc_session::DiagnosticMessageId;
 use rustc_span::symbol::{kw, sym, Ident, Symbol};
-use rustc_span::{BytePos, MultiSpan, Span, DUMMY_SP};
+use rustc_span::{BytePos, DesugaringKind, ExpnKind, MultiSpan, Span, DUMMY_SP};
 use rustc_target::spec::abi;
 use std::fmt;
 
 use super::InferCtxtPrivExt;
 use crate::traits::query::evaluate_obligation::InferCtxtExt as _;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum GeneratorInteriorOrUpvar {
     // span of interior type
     Interior(Span),
@@ -43,9 +50,15 @@ fn suggest_restricting_param_bound(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::PolyTraitRef<'tcx>,
-        body_id: hir::HirId,
+        obligation: &PredicateObligation<'tcx>,
     );
 
+    fn unmet_bounds_on_same_param(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> Vec<(String, DefId)>;
+
     fn suggest_dereferences(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -69,6 +82,30 @@ fn suggest_fn_call(
         points_at_arg: bool,
     );
 
+    fn suggest_call_of_callable_type(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        self_ty: Ty<'tcx>,
+        points_at_arg: bool,
+    );
+
+    fn suggest_closure_as_fn_ptr(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    );
+
+    fn suggest_path_string_conversion(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
     fn suggest_add_reference_to_arg(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -78,11 +115,49 @@ fn suggest_add_reference_to_arg(
         has_custom_message: bool,
     ) -> bool;
 
+    fn suggest_cloned_instead_of_clone(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    );
+
+    fn suggest_into_iter_instead_of_iter(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    );
+
+    fn suggest_as_ref_for_option_or_result(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
+    fn suggest_slice_or_vec_for_array(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
     fn suggest_remove_reference(
         &self,
         obligation: &PredicateObligation<'tcx>,
         err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        has_custom_message: bool,
+    ) -> bool;
+
+    fn suggest_iteration_method(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
     );
 
     fn suggest_change_mut(
@@ -91,6 +166,42 @@ fn suggest_change_mut(
         err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
         points_at_arg: bool,
+        has_custom_message: bool,
+    );
+
+    fn suggest_mut_reborrow(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) -> bool;
+
+    fn mut_reborrow_of_arg(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) -> Option<String>;
+
+    fn suggest_borrowing_index(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    );
+
+    fn suggest_swapping_type_params(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    );
+
+    fn suggest_dyn_trait_parameter(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
     );
 
     fn suggest_semicolon_removal(
@@ -101,6 +212,33 @@ fn suggest_semicolon_removal(
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
     );
 
+    fn suggest_moving_unsized_tuple_elem(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    );
+
+    fn suggest_derive(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> bool;
+
+    fn suggest_impl_for_local_type(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    );
+
+    fn suggest_flatten_nested_collection(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        predicate: ty::PolyProjectionPredicate<'tcx>,
+    );
+
     fn return_type_span(&self, obligation: &PredicateObligation<'tcx>) -> Option<Span>;
 
     fn suggest_impl_trait(
@@ -125,12 +263,49 @@ fn report_closure_arg_mismatch(
         found: ty::PolyTraitRef<'tcx>,
     ) -> DiagnosticBuilder<'tcx>;
 
+    fn suggest_closure_param_annotations(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        closure_ref: ty::PolyTraitRef<'tcx>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+    );
+
+    fn note_higher_ranked_closure_mismatch(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        closure_ref: ty::PolyTraitRef<'tcx>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+    );
+
+    fn note_conflicting_crate_versions(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+        found_ref: ty::PolyTraitRef<'tcx>,
+    );
+
     fn suggest_fully_qualified_path(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         def_id: DefId,
         span: Span,
         trait_ref: DefId,
+        body_id: hir::HirId,
+    );
+
+    fn suggest_add_type_annotation(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        body_id: Option<hir::BodyId>,
+    );
+
+    fn suggest_collect_target_type(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        target_ty: Ty<'tcx>,
+        body_id: Option<hir::BodyId>,
     );
 
     fn maybe_note_obligation_cause_for_async_await(
@@ -139,11 +314,49 @@ fn maybe_note_obligation_cause_for_async_await(
         obligation: &PredicateObligation<'tcx>,
     ) -> bool;
 
+    fn note_generator_holding_ty(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        generator_did: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        target_ty: Ty<'tcx>,
+        outer_generator: Option<DefId>,
+        next_code: Option<&ObligationCauseCode<'tcx>>,
+    ) -> bool;
+
+    fn note_awaits_of_inner_futures(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        generators: &[(DefId, ty::TraitRef<'tcx>, Ty<'tcx>)],
+    );
+
+    fn suggest_move_capture(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        generator_did: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        upvar_id: hir::HirId,
+        upvar_ty: Ty<'tcx>,
+    );
+
+    fn suggest_auto_trait_on_boxed_future(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        param_env: ty::ParamEnv<'tcx>,
+        trait_ref: ty::TraitRef<'tcx>,
+        target_ty: Ty<'tcx>,
+        held_expr: hir::HirId,
+        typeck_results: &TypeckResults<'tcx>,
+    );
+
     fn note_obligation_cause_for_async_await(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         interior_or_upvar_span: GeneratorInteriorOrUpvar,
         interior_extra_info: Option<(Option<Span>, Span, Option<hir::HirId>, Option<Span>)>,
+        moved_upvar: Option<hir::HirId>,
         inner_generator_body: Option<&hir::Body<'tcx>>,
         outer_generator: Option<DefId>,
         trait_ref: ty::TraitRef<'tcx>,
@@ -153,16 +366,45 @@ fn note_obligation_cause_for_async_await(
         next_code: Option<&ObligationCauseCode<'tcx>>,
     );
 
+    /// Notes where `cause_code` comes from, following derived obligations up to the requirement
+    /// that introduced them. With `-Z trait-cause-tree`, `tree_depth` is the number of steps of
+    /// that chain noted so far, which are then numbered and indented as a tree.
     fn note_obligation_cause_code<T>(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         predicate: &T,
+        cause: &ObligationCause<'tcx>,
         cause_code: &ObligationCauseCode<'tcx>,
-        obligated_types: &mut Vec<&ty::TyS<'tcx>>,
+        obligated_predicates: &mut Vec<ty::Predicate<'tcx>>,
         seen_requirements: &mut FxHashSet<DefId>,
+        tree_depth: Option<usize>,
     ) where
         T: fmt::Display;
 
+    /// When a bound on a method's `where` clause isn't met, label the call to that method too, so
+    /// that it's visible even when the error itself points at one of the call's arguments.
+    fn point_at_call_requiring_bound(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        cause: &ObligationCause<'tcx>,
+        method_def_id: DefId,
+    );
+
+    /// When an impl method is stricter than its trait method because of a `where` clause bound,
+    /// suggest adding the bound to the trait method, or splitting it off of the impl method's
+    /// `where` clause.
+    fn suggest_moving_impl_method_where_bound(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        predicate: ty::Predicate<'tcx>,
+        impl_item_def_id: DefId,
+        trait_item_def_id: DefId,
+    );
+
+    /// Whether `-Z terse-trait-suggestions` asked for trait errors to come with short help
+    /// messages instead of code suggestions, and without explanatory notes.
+    fn terse_suggestions(&self) -> bool;
+
     fn suggest_new_overflow_limit(&self, err: &mut DiagnosticBuilder<'_>);
 
     /// Suggest to await before try: future? => future.await?
@@ -173,6 +415,40 @@ fn suggest_await_before_try(
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
         span: Span,
     );
+
+    /// Suggest `?` on a `Result` or `Option` argument when its `Ok` or `Some` value would
+    /// satisfy the bound and can be propagated from the enclosing function: `res` => `res?`
+    fn suggest_question_mark_on_result(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
+    fn suggest_pinning(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
+    fn suggest_accessing_guarded_value(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
+
+    fn suggest_accessing_field(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    );
 }
 
 fn predicate_constraint(generics: &hir::Generics<'_>, pred: String) -> (Span, String) {
@@ -186,6 +462,54 @@ fn predicate_constraint(generics: &hir::Generics<'_>, pred: String) -> (Span, St
     )
 }
 
+/// Whether `ty` is a path to the type parameter `param_name`.
+fn is_param_path(ty: &hir::Ty<'_>, param_name: &str) -> bool {
+    match &ty.kind {
+        hir::TyKind::Path(hir::QPath::Resolved(None, hir::Path { segments: [segment], .. })) => {
+            segment.ident.as_str() == param_name
+        }
+        _ => false,
+    }
+}
+
+/// Whether `param_name` was relaxed with `?Sized`, either in its declaration or in the `where`
+/// clause of `generics`.
+fn has_maybe_sized_bound(generics: &hir::Generics<'_>, param_name: &str) -> bool {
+    let is_maybe = |bound: &hir::GenericBound<'_>| {
+        matches!(bound, hir::GenericBound::Trait(_, hir::TraitBoundModifier::Maybe))
+    };
+    let in_params = generics.params.iter().any(|param| {
+        param.name.ident().as_str() == param_name && param.bounds.iter().any(is_maybe)
+    });
+    let in_where_clause =
+        generics.where_clause.predicates.iter().any(|predicate| match predicate {
+            hir::WherePredicate::BoundPredicate(hir::WhereBoundPredicate {
+                bounded_ty,
+                bounds,
+                ..
+            }) => is_param_path(bounded_ty, param_name) && bounds.iter().any(is_maybe),
+            _ => false,
+        });
+    in_params || in_where_clause
+}
+
+/// The bounds of the last `where` predicate of `generics` that restricts `param_name`, so that
+/// new bounds on the same parameter can be grouped with them.
+fn where_predicate_bounds<'a>(
+    generics: &'a hir::Generics<'a>,
+    param_name: &str,
+) -> Option<&'a hir::GenericBounds<'a>> {
+    generics.where_clause.predicates.iter().rev().find_map(|predicate| match predicate {
+        hir::WherePredicate::BoundPredicate(hir::WhereBoundPredicate {
+            bounded_ty,
+            bounds,
+            bound_generic_params: [],
+            ..
+        }) if is_param_path(bounded_ty, param_name) => Some(bounds),
+        _ => None,
+    })
+}
+
 /// Type parameter needs more bounds. The trivial case is `T` `where T: Bound`, but
 /// it can also be an `impl Trait` param that needs to be decomposed to a type
 /// param for cleaner code.
@@ -287,10 +611,21 @@ fn suggest_restriction(
     } else {
         // Trivial case: `T` needs an extra bound: `T: Bound`.
         let (sp, suggestion) = match super_traits {
-            None => predicate_constraint(
-                generics,
-                trait_ref.without_const().to_predicate(tcx).to_string(),
-            ),
+            None => {
+                let self_ty = trait_ref.skip_binder().self_ty().to_string();
+                match where_predicate_bounds(generics, &self_ty) {
+                    // `fn foo<T, U>(t: T) where T: Foo, U: Bar`
+                    //                               ^ suggest `T: Foo + Bound` here
+                    Some([.., bound]) if !bound.span().from_expansion() => (
+                        bound.span().shrink_to_hi(),
+                        format!(" + {}", trait_ref.print_only_trait_path().to_string()),
+                    ),
+                    _ => predicate_constraint(
+                        generics,
+                        trait_ref.without_const().to_predicate(tcx).to_string(),
+                    ),
+                }
+            }
             Some((ident, bounds)) => match bounds {
                 [.., bound] => (
                     bound.span().shrink_to_hi(),
@@ -317,20 +652,91 @@ fn suggest_restricting_param_bound(
         &self,
         mut err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::PolyTraitRef<'tcx>,
-        body_id: hir::HirId,
+        obligation: &PredicateObligation<'tcx>,
     ) {
         let self_ty = trait_ref.skip_binder().self_ty();
         let (param_ty, projection) = match self_ty.kind() {
             ty::Param(_) => (true, None),
             ty::Projection(projection) => (false, Some(projection)),
+            ty::Opaque(def_id, _) => {
+                let suggestions_len = err.suggestions.len();
+                let constraint = trait_ref.print_only_trait_path().to_string();
+                if suggest_constraining_opaque_type(self.tcx, err, *def_id, &constraint) {
+                    err.categorize_suggestions_since(
+                        suggestions_len,
+                        SuggestionCategory::ConstrainParam,
+                    );
+                }
+                return;
+            }
             _ => return,
         };
 
+        // The suggestions are made by shared helpers, so we record whatever they added.
+        let suggestions_len = err.suggestions.len();
+
+        // A type parameter relaxed with `?Sized` on an `impl` or `trait` but required to be
+        // `Sized` in one of its methods only needs to be `Sized` for that method.
+        let is_sized = Some(trait_ref.def_id()) == self.tcx.lang_items().sized_trait();
+        let relaxed_by_parent = |hir_id| {
+            let parent = self.tcx.hir().get_parent_item(hir_id);
+            self.tcx
+                .hir()
+                .find(parent)
+                .and_then(|node| node.generics())
+                .map_or(false, |generics| has_maybe_sized_bound(generics, &self_ty.to_string()))
+        };
+
+        // Within its own trait, `Self::Assoc` is best restricted where the associated type is
+        // declared, so that every item of the trait (and every impl) can rely on the bound.
+        let assoc_type_decl = projection
+            .filter(|projection| projection.self_ty() == self.tcx.types.self_param)
+            .and_then(|projection| {
+                let trait_def_id = self.tcx.parent(projection.item_def_id)?;
+                match self.tcx.hir().get_if_local(projection.item_def_id)? {
+                    hir::Node::TraitItem(hir::TraitItem {
+                        ident,
+                        generics,
+                        kind: hir::TraitItemKind::Type(bounds, _),
+                        ..
+                    }) => Some((trait_def_id, ident, generics, bounds)),
+                    _ => None,
+                }
+            });
+        let is_assoc_type_trait = |hir_id| {
+            assoc_type_decl.map_or(false, |(trait_def_id, ..)| {
+                self.tcx.hir().local_def_id(hir_id).to_def_id() == trait_def_id
+            })
+        };
+
         // FIXME: Add check for trait bound that is already present, particularly `?Sized` so we
         //        don't suggest `T: Sized + ?Sized`.
-        let mut hir_id = body_id;
+        let mut hir_id = obligation.cause.body_id;
         while let Some(node) = self.tcx.hir().find(hir_id) {
             match node {
+                hir::Node::TraitItem(hir::TraitItem {
+                    kind: hir::TraitItemKind::Fn(..), ..
+                }) if is_assoc_type_trait(self.tcx.hir().get_parent_item(hir_id)) => {
+                    // Keep going up to the trait that declares the associated type.
+                }
+                hir::Node::Item(hir::Item { kind: hir::ItemKind::Trait(..), .. })
+                    if is_assoc_type_trait(hir_id) =>
+                {
+                    // Missing bound on the declaration of the associated type.
+                    let (_, ident, generics, bounds) = assoc_type_decl.unwrap();
+                    suggest_restriction(
+                        self.tcx,
+                        generics,
+                        "the associated type",
+                        err,
+                        None,
+                        projection,
+                        trait_ref,
+                        Some((ident, bounds)),
+                    );
+                    break;
+                }
+
                 hir::Node::Item(hir::Item {
                     ident,
                     kind: hir::ItemKind::Trait(_, _, generics, bounds, _),
@@ -348,7 +754,7 @@ fn suggest_restricting_param_bound(
                         trait_ref,
                         Some((ident, bounds)),
                     );
-                    return;
+                    break;
                 }
 
                 hir::Node::TraitItem(hir::TraitItem {
@@ -361,7 +767,7 @@ fn suggest_restricting_param_bound(
                     suggest_restriction(
                         self.tcx, &generics, "`Self`", err, None, projection, trait_ref, None,
                     );
-                    return;
+                    break;
                 }
 
                 hir::Node::TraitItem(hir::TraitItem {
@@ -388,7 +794,7 @@ fn suggest_restricting_param_bound(
                         trait_ref,
                         None,
                     );
-                    return;
+                    break;
                 }
                 hir::Node::Item(hir::Item {
                     kind:
@@ -407,7 +813,31 @@ fn suggest_restricting_param_bound(
                         trait_ref,
                         None,
                     );
-                    return;
+                    break;
+                }
+
+                hir::Node::TraitItem(hir::TraitItem {
+                    generics,
+                    kind: hir::TraitItemKind::Fn(..),
+                    ..
+                })
+                | hir::Node::ImplItem(hir::ImplItem {
+                    generics,
+                    kind: hir::ImplItemKind::Fn(..),
+                    ..
+                }) if param_ty && is_sized && relaxed_by_parent(hir_id) => {
+                    // Require `Sized` for this method rather than undoing the relaxation.
+                    suggest_restriction(
+                        self.tcx,
+                        &generics,
+                        &format!("type parameter `{}`", self_ty),
+                        err,
+                        None,
+                        None,
+                        trait_ref,
+                        None,
+                    );
+                    break;
                 }
 
                 hir::Node::Item(hir::Item {
@@ -429,26 +859,101 @@ fn suggest_restricting_param_bound(
                 {
                     // Missing generic type parameter bound.
                     let param_name = self_ty.to_string();
-                    let constraint = trait_ref.print_only_trait_path().to_string();
-                    if suggest_constraining_type_param(
+                    let mut constraints = vec![];
+                    if param_name.starts_with("impl ") {
+                        // An `impl Trait` argument is restricted in place, so suggest every bound
+                        // it is missing at once rather than one per error.
+                        constraints = self.unmet_bounds_on_same_param(obligation, trait_ref);
+                    }
+                    if constraints.is_empty() {
+                        let constraint = trait_ref.print_only_trait_path().to_string();
+                        constraints.push((constraint, trait_ref.def_id()));
+                    }
+                    let constraints = constraints
+                        .iter()
+                        .map(|(constraint, def_id)| (constraint.as_str(), Some(*def_id)))
+                        .collect::<Vec<_>>();
+                    if suggest_constraining_type_params(
                         self.tcx,
                         generics,
                         &mut err,
                         &param_name,
-                        &constraint,
-                        Some(trait_ref.def_id()),
+                        &constraints,
                     ) {
-                        return;
+                        break;
                     }
+                    // The parameter isn't declared here, e.g. it belongs to the `impl` or `trait`
+                    // around this method, so keep looking in the enclosing item's generics.
                 }
 
-                hir::Node::Crate(..) => return,
+                hir::Node::Crate(..) => break,
 
                 _ => {}
             }
 
             hir_id = self.tcx.hir().get_parent_item(hir_id);
         }
+
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::ConstrainParam);
+    }
+
+    /// When `trait_ref` comes from a bound on a generic parameter of an item, returns every
+    /// trait bound on that same parameter that its argument doesn't meet, in the order the
+    /// bounds were written, including `trait_ref` itself. Returns an empty list otherwise.
+    fn unmet_bounds_on_same_param(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> Vec<(String, DefId)> {
+        let (item_def_id, span) = match obligation.cause.code {
+            ObligationCauseCode::BindingObligation(item_def_id, span) => (item_def_id, span),
+            _ => return vec![],
+        };
+        let predicates = self.tcx.predicates_of(item_def_id).predicates;
+        let trait_preds = || {
+            predicates.iter().filter_map(|(predicate, span)| match predicate.skip_binders() {
+                ty::PredicateAtom::Trait(trait_pred, _) => Some((trait_pred, *span)),
+                _ => None,
+            })
+        };
+
+        // Find the parameter the failing bound was written on.
+        let param_ty = trait_preds().find_map(|(trait_pred, pred_span)| {
+            (pred_span == span && trait_pred.def_id() == trait_ref.def_id())
+                .then(|| trait_pred.self_ty())
+        });
+        let param_ty = match param_ty {
+            Some(param_ty) if matches!(param_ty.kind(), ty::Param(_)) => param_ty,
+            _ => return vec![],
+        };
+
+        let self_ty = trait_ref.skip_binder().self_ty();
+        let unmet = trait_preds()
+            .filter(|(trait_pred, _)| {
+                // Bounds with generic arguments of their own would need those substituted.
+                trait_pred.self_ty() == param_ty && trait_pred.trait_ref.substs.len() == 1
+            })
+            .filter(|(trait_pred, _)| {
+                trait_pred.def_id() == trait_ref.def_id()
+                    || Some(trait_pred.def_id()) != self.tcx.lang_items().sized_trait() && {
+                        let obligation = self.mk_trait_obligation_with_new_self_ty(
+                            obligation.param_env,
+                            ty::Binder::dummy(trait_pred.trait_ref),
+                            self_ty,
+                        );
+                        !self.predicate_must_hold_modulo_regions(&obligation)
+                    }
+            })
+            .map(|(trait_pred, _)| {
+                let constraint =
+                    ty::Binder::dummy(trait_pred.trait_ref).print_only_trait_path().to_string();
+                (constraint, trait_pred.def_id())
+            })
+            .collect::<Vec<_>>();
+        if !unmet.iter().any(|&(_, def_id)| def_id == trait_ref.def_id()) {
+            return vec![];
+        }
+        unmet
     }
 
     /// When after several dereferencing, the reference satisfies the trait
@@ -481,12 +986,13 @@ fn suggest_dereferences(
 
         if let ty::Ref(region, base_ty, mutbl) = *real_ty.kind() {
             let mut autoderef = Autoderef::new(self, param_env, body_id, span, base_ty, span);
-            if let Some(steps) = autoderef.find_map(|(ty, steps)| {
+            if let Some((steps, obligation)) = autoderef.find_map(|(ty, steps)| {
                 // Re-add the `&`
                 let ty = self.tcx.mk_ref(region, TypeAndMut { ty, mutbl });
                 let obligation =
                     self.mk_trait_obligation_with_new_self_ty(param_env, real_trait_ref, ty);
-                Some(steps).filter(|_| self.predicate_may_hold(&obligation))
+                Some((steps, obligation))
+                    .filter(|(_, obligation)| self.predicate_may_hold(obligation))
             }) {
                 if steps > 0 {
                     if let Ok(src) = self.tcx.sess.source_map().span_to_snippet(span) {
@@ -494,11 +1000,23 @@ fn suggest_dereferences(
                         // often and user will not expect autoderef happens.
                         if src.starts_with('&') && !src.starts_with("&mut ") {
                             let derefs = "*".repeat(steps);
+                            // The bound only may hold when inference variables are involved.
+                            let applicability =
+                                if self.predicate_must_hold_modulo_regions(&obligation) {
+                                    Applicability::MachineApplicable
+                                } else {
+                                    Applicability::MaybeIncorrect
+                                };
+                            let suggestions_len = err.suggestions.len();
                             err.span_suggestion(
                                 span,
                                 "consider adding dereference here",
                                 format!("&{}{}", derefs, &src[1..]),
-                                Applicability::MachineApplicable,
+                                applicability,
+                            );
+                            err.categorize_suggestions_since(
+                                suggestions_len,
+                                SuggestionCategory::Access,
                             );
                         }
                     }
@@ -561,8 +1079,20 @@ fn suggest_fn_call(
         };
 
         let (def_id, output_ty, callable) = match *self_ty.kind() {
-            ty::Closure(def_id, substs) => (def_id, substs.as_closure().sig().output(), "closure"),
-            ty::FnDef(def_id, _) => (def_id, self_ty.fn_sig(self.tcx).output(), "function"),
+            ty::Closure(def_id, substs) => {
+                (Some(def_id), substs.as_closure().sig().output(), "closure")
+            }
+            ty::FnDef(def_id, _) => (Some(def_id), self_ty.fn_sig(self.tcx).output(), "function"),
+            ty::FnPtr(sig) => (None, sig.output(), "function pointer"),
+            ty::Adt(..) => {
+                return self.suggest_call_of_callable_type(
+                    obligation,
+                    err,
+                    trait_ref,
+                    self_ty,
+                    points_at_arg,
+                );
+            }
             _ => return,
         };
         let msg = format!("use parentheses to call the {}", callable);
@@ -577,29 +1107,43 @@ fn suggest_fn_call(
         let new_obligation =
             self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, output_ty);
 
-        match self.evaluate_obligation(&new_obligation) {
-            Ok(
-                EvaluationResult::EvaluatedToOk
-                | EvaluationResult::EvaluatedToOkModuloRegions
-                | EvaluationResult::EvaluatedToAmbig,
-            ) => {}
+        // When it isn't known whether the returned value satisfies the bound, calling the
+        // callable is only mentioned rather than suggested as an edit.
+        let is_ambiguous = match self.evaluate_obligation(&new_obligation) {
+            Ok(EvaluationResult::EvaluatedToOk | EvaluationResult::EvaluatedToOkModuloRegions) => {
+                false
+            }
+            Ok(EvaluationResult::EvaluatedToAmbig) => true,
             _ => return,
-        }
+        };
         let hir = self.tcx.hir();
+        // Use the parameter names as placeholders when they are plain bindings, so that the
+        // suggestion reads like the signature of the callable.
+        let args_from_body = |body_id: hir::BodyId| {
+            hir.body(body_id)
+                .params
+                .iter()
+                .map(|arg| match &arg.pat.kind {
+                    hir::PatKind::Binding(_, _, ident, None)
+                    // FIXME: provide a better suggestion when encountering `SelfLower`, it
+                    // should suggest a method call.
+                    if ident.name != kw::SelfLower => ident.to_string(),
+                    _ => "_".to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
         // Get the name of the callable and the arguments to be used in the suggestion.
-        let (snippet, sugg) = match hir.get_if_local(def_id) {
+        let (name, args) = match def_id.and_then(|def_id| hir.get_if_local(def_id)) {
             Some(hir::Node::Expr(hir::Expr {
-                kind: hir::ExprKind::Closure(_, decl, _, span, ..),
+                kind: hir::ExprKind::Closure(_, _, body_id, span, ..),
                 ..
             })) => {
                 err.span_label(*span, "consider calling this closure");
-                let name = match self.get_closure_name(def_id, err, &msg) {
+                let name = match self.get_closure_name(def_id.unwrap(), err, &msg) {
                     Some(name) => name,
                     None => return,
                 };
-                let args = decl.inputs.iter().map(|_| "_").collect::<Vec<_>>().join(", ");
-                let sugg = format!("({})", args);
-                (format!("{}{}", name, sugg), sugg)
+                (name, args_from_body(*body_id))
             }
             Some(hir::Node::Item(hir::Item {
                 ident,
@@ -607,40 +1151,301 @@ fn suggest_fn_call(
                 ..
             })) => {
                 err.span_label(ident.span, "consider calling this function");
-                let body = hir.body(*body_id);
-                let args = body
-                    .params
-                    .iter()
-                    .map(|arg| match &arg.pat.kind {
-                        hir::PatKind::Binding(_, _, ident, None)
-                        // FIXME: provide a better suggestion when encountering `SelfLower`, it
-                        // should suggest a method call.
-                        if ident.name != kw::SelfLower => ident.to_string(),
-                        _ => "_".to_string(),
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let sugg = format!("({})", args);
-                (format!("{}{}", ident, sugg), sugg)
+                (ident.to_string(), args_from_body(*body_id))
+            }
+            // A function pointer has no definition to point at, so refer to it by the
+            // expression it was passed as.
+            None if def_id.is_none() => {
+                let inputs_len = self_ty.fn_sig(self.tcx).inputs().skip_binder().len();
+                let source_map = self.tcx.sess.source_map();
+                let name = match source_map.span_to_snippet(obligation.cause.span) {
+                    Ok(snippet) => snippet,
+                    Err(_) => return,
+                };
+                (name, vec!["_".to_string(); inputs_len])
             }
             _ => return,
         };
-        if points_at_arg {
+        // Without arguments there is nothing left for the user to fill in.
+        let applicability = if args.is_empty() {
+            Applicability::MachineApplicable
+        } else {
+            Applicability::HasPlaceholders
+        };
+        let sugg = format!("({})", args.join(", "));
+        let snippet = format!("{}{}", name, sugg);
+        if points_at_arg && !is_ambiguous {
             // When the obligation error has been ensured to have been caused by
             // an argument, the `obligation.cause.span` points at the expression
             // of the argument, so we can provide a suggestion. This is signaled
             // by `points_at_arg`. Otherwise, we give a more general note.
+            let suggestions_len = err.suggestions.len();
             err.span_suggestion_verbose(
                 obligation.cause.span.shrink_to_hi(),
                 &msg,
                 sugg,
-                Applicability::HasPlaceholders,
+                applicability,
             );
+            err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Call);
         } else {
             err.help(&format!("{}: `{}`", msg, snippet));
         }
     }
 
+    /// We tried to apply the bound to a value of a type that implements `FnOnce` by hand, like
+    /// a callable struct. Check whether calling it would evaluate to a type that *would* satisfy
+    /// the trait binding. If it would, suggest calling it: `bar(adder)` → `bar(adder(_))`.
+    fn suggest_call_of_callable_type(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        self_ty: Ty<'tcx>,
+        points_at_arg: bool,
+    ) {
+        // Without pointing at the argument we don't have a name to call, so there's nothing
+        // useful to suggest.
+        if !points_at_arg {
+            return;
+        }
+        let tcx = self.tcx;
+        let fn_once_trait = match tcx.lang_items().fn_once_trait() {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        // Find the argument tuple of the `FnOnce` impl for this exact type. Every `Fn` or
+        // `FnMut` impl is accompanied by a `FnOnce` one, so this covers all of them.
+        let inputs = match tcx.find_map_relevant_impl(fn_once_trait, self_ty, |impl_def_id| {
+            let impl_trait_ref = tcx.impl_trait_ref(impl_def_id)?;
+            if impl_trait_ref.self_ty() != self_ty || impl_trait_ref.needs_subst() {
+                return None;
+            }
+            Some(impl_trait_ref.substs.type_at(1))
+        }) {
+            Some(inputs) => inputs,
+            None => return,
+        };
+        let inputs_len = match inputs.kind() {
+            ty::Tuple(tys) => tys.len(),
+            _ => return,
+        };
+
+        // `<T as FnOnce<Args>>::Output`
+        let projection_ty = ty::ProjectionTy {
+            substs: tcx.mk_substs_trait(self_ty, &[inputs.into()]),
+            item_def_id: tcx.require_lang_item(LangItem::FnOnceOutput, None),
+        };
+        let mut selcx = SelectionContext::new(self);
+        let mut obligations = vec![];
+        let output_ty = normalize_projection_type(
+            &mut selcx,
+            obligation.param_env,
+            projection_ty,
+            obligation.cause.clone(),
+            0,
+            &mut obligations,
+        );
+
+        let new_obligation =
+            self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, output_ty);
+        match self.evaluate_obligation(&new_obligation) {
+            Ok(
+                EvaluationResult::EvaluatedToOk
+                | EvaluationResult::EvaluatedToOkModuloRegions
+                | EvaluationResult::EvaluatedToAmbig,
+            ) => {}
+            _ => return,
+        }
+
+        let sugg = format!("({})", vec!["_"; inputs_len].join(", "));
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            obligation.cause.span.shrink_to_hi(),
+            &format!("use parentheses to call the value of type `{}`", self_ty),
+            sugg,
+            Applicability::HasPlaceholders,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Call);
+    }
+
+    /// A closure that captures nothing can be used as a function pointer, but only where it gets
+    /// coerced to one, which doesn't happen to satisfy a trait bound. When the bound would hold for
+    /// the function pointer, suggest casting the closure to it.
+    fn suggest_closure_as_fn_ptr(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let (def_id, substs) = match trait_ref.self_ty().no_bound_vars().map(|ty| ty.kind()) {
+            Some(&ty::Closure(def_id, substs)) => (def_id, substs),
+            _ => return,
+        };
+        if self.tcx.upvars_mentioned(def_id).map_or(false, |upvars| !upvars.is_empty()) {
+            return;
+        }
+        let fn_ptr_ty = self.resolve_vars_if_possible(substs.as_closure().sig_as_fn_ptr_ty());
+        if fn_ptr_ty.has_infer_types_or_consts() {
+            return;
+        }
+        let new_obligation =
+            self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, fn_ptr_ty);
+        if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+            return;
+        }
+        let span = match self.tcx.hir().span_if_local(def_id) {
+            Some(span) if !span.from_expansion() => span,
+            _ => return,
+        };
+        let suggestions_len = err.suggestions.len();
+        err.multipart_suggestion(
+            "a closure that captures nothing can be cast to a function pointer",
+            vec![
+                (span.shrink_to_lo(), "(".to_string()),
+                (span.shrink_to_hi(), format!(") as {}", fn_ptr_ty)),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+        err.help(&format!(
+            "alternatively, move the closure's body into a `fn` item and cast that to `{}`",
+            fn_ptr_ty,
+        ));
+    }
+
+    /// When a string is passed where a bound wants a path, or a path is passed where a bound
+    /// wants a string, suggest converting between them: `open(name)` →
+    /// `open(std::path::Path::new(&name))`. Converting a path into a string is lossy, so that
+    /// suggestion is only `MaybeIncorrect`.
+    fn suggest_path_string_conversion(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        if !points_at_arg {
+            return;
+        }
+        let tcx = self.tcx;
+        let span = obligation.cause.span;
+        // With blanket impls like `impl<T, U: From<T>> Into<U> for T`, the bound that was
+        // written for the argument is the parent one.
+        let trait_ref = match &obligation.cause.code {
+            ObligationCauseCode::ImplDerivedObligation(data) => data.parent_trait_ref,
+            _ => trait_ref,
+        };
+        let self_ty = match self.resolve_vars_if_possible(trait_ref.self_ty()).no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() => ty,
+            _ => return,
+        };
+        let snippet = match tcx.sess.source_map().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        let (pointee, is_ref) = match *self_ty.kind() {
+            ty::Ref(_, ty, _) => (ty, true),
+            _ => (self_ty, false),
+        };
+        let is_adt = |ty: Ty<'tcx>, name| match ty.kind() {
+            ty::Adt(def, _) => tcx.is_diagnostic_item(name, def.did),
+            _ => false,
+        };
+        let is_path_like = |ty: Ty<'tcx>| {
+            is_adt(ty, sym::path_type)
+                || is_adt(ty, sym::pathbuf_type)
+                || is_adt(ty, sym::osstring_type)
+        };
+        let is_string_like = |ty: Ty<'tcx>| ty.is_str() || is_adt(ty, sym::string_type);
+        // Only look at bounds that are about paths or strings, like `Into<Box<Path>>` or
+        // `AsRef<str>`, otherwise we'd suggest this for any bound that `&Path` happens to meet.
+        let is_path_related_bound = trait_ref.skip_binder().substs.iter().skip(1).any(|arg| {
+            arg.walk().any(|arg| match arg.unpack() {
+                GenericArgKind::Type(ty) => is_path_like(ty) || is_string_like(ty),
+                _ => false,
+            })
+        });
+        if !is_path_related_bound {
+            return;
+        }
+
+        // The conversions to try, in order of preference, as the type they produce, how to
+        // refer to it, the replacement for the argument and whether they can lose information.
+        let mut conversions = vec![];
+        if is_string_like(pointee) {
+            let (path_def_id, pathbuf_def_id) = match (
+                tcx.get_diagnostic_item(sym::path_type),
+                tcx.get_diagnostic_item(sym::pathbuf_type),
+            ) {
+                (Some(path), Some(pathbuf)) => (path, pathbuf),
+                _ => return,
+            };
+            // `Path::new` takes its argument by reference.
+            let borrow = if is_ref { "" } else { "&" };
+            conversions.push((
+                tcx.mk_imm_ref(tcx.lifetimes.re_static, tcx.type_of(path_def_id)),
+                "&Path",
+                format!(
+                    "{}::new({}{})",
+                    with_no_trimmed_paths(|| tcx.def_path_str(path_def_id)),
+                    borrow,
+                    snippet,
+                ),
+                false,
+            ));
+            conversions.push((
+                tcx.type_of(pathbuf_def_id),
+                "PathBuf",
+                format!(
+                    "{}::from({})",
+                    with_no_trimmed_paths(|| tcx.def_path_str(pathbuf_def_id)),
+                    snippet,
+                ),
+                false,
+            ));
+        } else if is_path_like(pointee) {
+            let string_ty = match tcx.get_diagnostic_item(sym::string_type) {
+                Some(def_id) => tcx.type_of(def_id),
+                None => return,
+            };
+            // Method calls auto-reference their receiver, so any explicit borrow is redundant.
+            let receiver = snippet.strip_prefix('&').map_or(&snippet[..], |s| s.trim_start());
+            conversions.push((
+                string_ty,
+                "String",
+                format!("{}.to_string_lossy().into_owned()", receiver),
+                true,
+            ));
+        }
+
+        for (new_ty, new_ty_name, sugg, is_lossy) in conversions {
+            let new_obligation =
+                self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, new_ty);
+            if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+                continue;
+            }
+            let (msg, applicability) = if is_lossy {
+                (
+                    format!(
+                        "consider converting the `{}` into a `{}`, replacing any non-UTF-8 \
+                         sequences with `U+FFFD`",
+                        self_ty, new_ty_name,
+                    ),
+                    Applicability::MaybeIncorrect,
+                )
+            } else {
+                (
+                    format!("consider converting the `{}` into a `{}`", self_ty, new_ty_name),
+                    Applicability::MachineApplicable,
+                )
+            };
+            let suggestions_len = err.suggestions.len();
+            err.span_suggestion_verbose(span, &msg, sugg, applicability);
+            err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+            return;
+        }
+    }
+
     fn suggest_add_reference_to_arg(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -688,7 +1493,8 @@ fn suggest_add_reference_to_arg(
                     } else {
                         err.message = vec![(msg, Style::NoStyle)];
                     }
-                    if snippet.starts_with('&') {
+                    let already_borrowed = snippet.starts_with('&');
+                    if already_borrowed && !self_ty.is_ref() {
                         // This is already a literal borrow and the obligation is failing
                         // somewhere else in the obligation chain. Do not suggest non-sense.
                         return false;
@@ -710,12 +1516,23 @@ fn suggest_add_reference_to_arg(
                         // }
                         // ```
 
+                        // When the argument is already a borrow, the bound holds for a borrow
+                        // of the borrow instead.
+                        let suggestions_len = err.suggestions.len();
                         err.span_suggestion(
                             span,
-                            "consider borrowing here",
+                            if already_borrowed {
+                                "consider borrowing the reference here"
+                            } else {
+                                "consider borrowing here"
+                            },
                             format!("&{}", snippet),
                             Applicability::MaybeIncorrect,
                         );
+                        err.categorize_suggestions_since(
+                            suggestions_len,
+                            SuggestionCategory::Borrow,
+                        );
                     }
                     return true;
                 }
@@ -724,67 +1541,602 @@ fn suggest_add_reference_to_arg(
         false
     }
 
-    /// Whenever references are used by mistake, like `for (i, e) in &vec.iter().enumerate()`,
-    /// suggest removing these references until we reach a type that implements the trait.
-    fn suggest_remove_reference(
+    /// When the receiver of a method is `iter.clone()` and the method's bound would hold for the
+    /// iterator's items if they weren't references, the user likely meant to clone the items
+    /// rather than the iterator: `iter.clone().collect()` → `iter.cloned().collect()`.
+    fn suggest_cloned_instead_of_clone(
         &self,
         obligation: &PredicateObligation<'tcx>,
         err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
     ) {
-        let span = obligation.cause.span;
+        let tcx = self.tcx;
+        let trait_ref = match self.resolve_vars_if_possible(trait_ref).no_bound_vars() {
+            Some(trait_ref) if !trait_ref.has_infer_types_or_consts() => trait_ref,
+            _ => return,
+        };
+        let (iterator, clone_trait) =
+            match (tcx.get_diagnostic_item(sym::Iterator), tcx.lang_items().clone_trait()) {
+                (Some(iterator), Some(clone_trait)) => (iterator, clone_trait),
+                _ => return,
+            };
+        let implements = |trait_def_id, ty| {
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs_trait(ty, &[]));
+            let obligation = Obligation::new(
+                obligation.cause.clone(),
+                obligation.param_env,
+                trait_ref.without_const().to_predicate(tcx),
+            );
+            self.predicate_must_hold_modulo_regions(&obligation)
+        };
 
-        if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
-            let refs_number =
-                snippet.chars().filter(|c| !c.is_whitespace()).take_while(|c| *c == '&').count();
-            if let Some('\'') = snippet.chars().filter(|c| !c.is_whitespace()).nth(refs_number) {
-                // Do not suggest removal of borrow from type arguments.
-                return;
-            }
+        // The obligation points at the method, look for a `.clone()` receiver.
+        let typeck_results = match self.in_progress_typeck_results {
+            Some(typeck_results) => typeck_results.borrow(),
+            None => return,
+        };
+        let body = match cause_body(tcx.hir(), &obligation.cause) {
+            Some(body) => body,
+            None => return,
+        };
+        let mut visitor = CallArgsVisitor { callee_span: obligation.cause.span, args: &[] };
+        visitor.visit_body(body);
+        let (clone_expr, clone_segment) = match visitor.args.first() {
+            Some(expr) => match &expr.kind {
+                hir::ExprKind::MethodCall(segment, _, [_], _)
+                    if segment.ident.name == sym::clone =>
+                {
+                    (expr, segment)
+                }
+                _ => return,
+            },
+            None => return,
+        };
+        let iter_ty = match typeck_results.expr_ty_opt(clone_expr) {
+            Some(ty) => self.resolve_vars_if_possible(ty),
+            None => return,
+        };
+        if iter_ty.has_infer_types_or_consts() || !implements(iterator, iter_ty) {
+            return;
+        }
 
-            let mut suggested_ty = match trait_ref.self_ty().no_bound_vars() {
-                Some(ty) => ty,
+        // `<I as Iterator>::Item`
+        let item_def_id =
+            match tcx.associated_items(iterator).filter_by_name_unhygienic(sym::Item).next() {
+                Some(item) => item.def_id,
                 None => return,
             };
+        let mut selcx = SelectionContext::new(self);
+        let mut obligations = vec![];
+        let item_ty = normalize_projection_type(
+            &mut selcx,
+            obligation.param_env,
+            ty::ProjectionTy { substs: tcx.mk_substs_trait(iter_ty, &[]), item_def_id },
+            obligation.cause.clone(),
+            0,
+            &mut obligations,
+        );
+        let item_ty = self.resolve_vars_if_possible(item_ty);
+        let cloned_item_ty = match *item_ty.kind() {
+            ty::Ref(_, ty, _) if !ty.has_infer_types_or_consts() => ty,
+            _ => return,
+        };
+        if !implements(clone_trait, cloned_item_ty) {
+            return;
+        }
 
-            for refs_remaining in 0..refs_number {
-                if let ty::Ref(_, inner_ty, _) = suggested_ty.kind() {
-                    suggested_ty = inner_ty;
-
-                    let new_obligation = self.mk_trait_obligation_with_new_self_ty(
-                        obligation.param_env,
-                        trait_ref,
-                        suggested_ty,
-                    );
+        // Only suggest this if the bound is about the items and would hold for cloned ones.
+        let is_item =
+            |arg: GenericArg<'tcx>| tcx.erase_regions(arg) == tcx.erase_regions(item_ty).into();
+        if !trait_ref.substs.iter().any(is_item) {
+            return;
+        }
+        let substs = tcx.mk_substs(
+            trait_ref
+                .substs
+                .iter()
+                .map(|arg| if is_item(arg) { cloned_item_ty.into() } else { arg }),
+        );
+        let new_obligation = Obligation::new(
+            obligation.cause.clone(),
+            obligation.param_env,
+            ty::TraitRef::new(trait_ref.def_id, substs).without_const().to_predicate(tcx),
+        );
+        if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+            return;
+        }
 
-                    if self.predicate_may_hold(&new_obligation) {
-                        let sp = self
-                            .tcx
-                            .sess
-                            .source_map()
-                            .span_take_while(span, |c| c.is_whitespace() || *c == '&');
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            clone_segment.ident.span,
+            "consider using `cloned` to clone the items of the iterator instead of the iterator \
+             itself",
+            "cloned".to_owned(),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+    }
 
-                        let remove_refs = refs_remaining + 1;
+    /// When the receiver of a method is `coll.iter()` and the method's bound would hold for the
+    /// items by value, suggest consuming the collection instead if it is an owned local that
+    /// yields those items: `v.iter().collect::<Vec<String>>()` → `v.into_iter().collect()`.
+    /// Whether `coll` is still used afterwards is up to borrowck, so this may not apply.
+    fn suggest_into_iter_instead_of_iter(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let tcx = self.tcx;
+        let trait_ref = match self.resolve_vars_if_possible(trait_ref).no_bound_vars() {
+            Some(trait_ref) if !trait_ref.has_infer_types_or_consts() => trait_ref,
+            _ => return,
+        };
+        let (iterator, into_iterator) = match (
+            tcx.get_diagnostic_item(sym::Iterator),
+            tcx.get_diagnostic_item(sym::IntoIterator),
+        ) {
+            (Some(iterator), Some(into_iterator)) => (iterator, into_iterator),
+            _ => return,
+        };
 
-                        let msg = if remove_refs == 1 {
-                            "consider removing the leading `&`-reference".to_string()
-                        } else {
-                            format!("consider removing {} leading `&`-references", remove_refs)
+        // The obligation points at the method, look for a `coll.iter()` receiver where `coll` is
+        // a local that can be moved out of.
+        let typeck_results = match self.in_progress_typeck_results {
+            Some(typeck_results) => typeck_results.borrow(),
+            None => return,
+        };
+        let body = match cause_body(tcx.hir(), &obligation.cause) {
+            Some(body) => body,
+            None => return,
+        };
+        let mut visitor = CallArgsVisitor { callee_span: obligation.cause.span, args: &[] };
+        visitor.visit_body(body);
+        let (iter_expr, iter_segment, coll_expr) = match visitor.args.first() {
+            Some(expr) => match &expr.kind {
+                hir::ExprKind::MethodCall(segment, _, [coll], _)
+                    if segment.ident.name == sym::iter =>
+                {
+                    (expr, segment, coll)
+                }
+                _ => return,
+            },
+            None => return,
+        };
+        match coll_expr.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path))
+                if matches!(path.res, Res::Local(_)) => {}
+            _ => return,
+        }
+        let expr_ty = |expr: &hir::Expr<'_>| match typeck_results.expr_ty_opt(expr) {
+            Some(ty) => Some(self.resolve_vars_if_possible(ty)),
+            None => None,
+        };
+        let (iter_ty, coll_ty) = match (expr_ty(iter_expr), expr_ty(coll_expr)) {
+            (Some(iter_ty), Some(coll_ty)) => (iter_ty, coll_ty),
+            _ => return,
+        };
+        if iter_ty.has_infer_types_or_consts()
+            || coll_ty.has_infer_types_or_consts()
+            || coll_ty.is_ref()
+        {
+            return;
+        }
+
+        // `<I as Iterator>::Item` and `<C as IntoIterator>::Item`
+        let item_ty = |trait_def_id, self_ty| {
+            let item_def_id = tcx
+                .associated_items(trait_def_id)
+                .filter_by_name_unhygienic(sym::Item)
+                .next()?
+                .def_id;
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs_trait(self_ty, &[]));
+            let trait_obligation = Obligation::new(
+                obligation.cause.clone(),
+                obligation.param_env,
+                trait_ref.without_const().to_predicate(tcx),
+            );
+            if !self.predicate_must_hold_modulo_regions(&trait_obligation) {
+                return None;
+            }
+            let mut selcx = SelectionContext::new(self);
+            let mut obligations = vec![];
+            let ty = normalize_projection_type(
+                &mut selcx,
+                obligation.param_env,
+                ty::ProjectionTy { substs: trait_ref.substs, item_def_id },
+                obligation.cause.clone(),
+                0,
+                &mut obligations,
+            );
+            Some(self.resolve_vars_if_possible(ty))
+        };
+        let (borrowed_item_ty, owned_item_ty) =
+            match (item_ty(iterator, iter_ty), item_ty(into_iterator, coll_ty)) {
+                (Some(borrowed), Some(owned)) => (borrowed, owned),
+                _ => return,
+            };
+        match *borrowed_item_ty.kind() {
+            ty::Ref(_, ty, _) if tcx.erase_regions(ty) == tcx.erase_regions(owned_item_ty) => {}
+            _ => return,
+        }
+        if owned_item_ty.has_infer_types_or_consts() {
+            return;
+        }
+
+        // Only suggest this if the bound is about the items and would hold for owned ones.
+        let is_item = |arg: GenericArg<'tcx>| {
+            tcx.erase_regions(arg) == tcx.erase_regions(borrowed_item_ty).into()
+        };
+        if !trait_ref.substs.iter().any(is_item) {
+            return;
+        }
+        let substs = tcx.mk_substs(
+            trait_ref
+                .substs
+                .iter()
+                .map(|arg| if is_item(arg) { owned_item_ty.into() } else { arg }),
+        );
+        let new_obligation = Obligation::new(
+            obligation.cause.clone(),
+            obligation.param_env,
+            ty::TraitRef::new(trait_ref.def_id, substs).without_const().to_predicate(tcx),
+        );
+        if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+            return;
+        }
+
+        let coll = match tcx.sess.source_map().span_to_snippet(coll_expr.span) {
+            Ok(snippet) => format!("`{}`", snippet),
+            Err(_) => "the collection".to_string(),
+        };
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            iter_segment.ident.span,
+            &format!(
+                "consider consuming {} with `into_iter` to iterate over its items by value",
+                coll
+            ),
+            "into_iter".to_owned(),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+    }
+
+    /// `Option` and `Result` don't forward traits to the value they hold. When a bound doesn't
+    /// hold for one of them but would for the wrapper of borrowed values, suggest `.as_ref()` or
+    /// `.as_mut()`; when it would hold for the value inside, point out that it has to be matched
+    /// on first.
+    fn suggest_as_ref_for_option_or_result(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let tcx = self.tcx;
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        // Borrowing would only sidestep these, not help.
+        let lang_items = tcx.lang_items();
+        if [lang_items.copy_trait(), lang_items.clone_trait(), lang_items.sized_trait()]
+            .contains(&Some(trait_ref.def_id()))
+            || tcx.trait_is_auto(trait_ref.def_id())
+        {
+            return;
+        }
+        let self_ty = match trait_ref.self_ty().no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() => ty,
+            _ => return,
+        };
+        let (def, substs) = match *self_ty.kind() {
+            ty::Adt(def, substs)
+                if tcx.is_diagnostic_item(sym::option_type, def.did)
+                    || tcx.is_diagnostic_item(sym::result_type, def.did) =>
+            {
+                (def, substs)
+            }
+            _ => return,
+        };
+        let wrapper = tcx.item_name(def.did);
+        let holds = |ty| {
+            let obligation =
+                self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, ty);
+            self.predicate_may_hold(&obligation)
+        };
+
+        let span = obligation.cause.span;
+        if points_at_arg && !span.from_expansion() {
+            for &(mutbl, method) in
+                &[(hir::Mutability::Not, "as_ref"), (hir::Mutability::Mut, "as_mut")]
+            {
+                // `Option<&T>` or `Result<&T, &E>`
+                let borrowed_substs = tcx.mk_substs(substs.iter().map(|arg| match arg.unpack() {
+                    GenericArgKind::Type(ty) => {
+                        tcx.mk_ref(tcx.lifetimes.re_static, TypeAndMut { ty, mutbl }).into()
+                    }
+                    _ => arg,
+                }));
+                if holds(tcx.mk_adt(def, borrowed_substs)) {
+                    let suggestions_len = err.suggestions.len();
+                    err.span_suggestion_verbose(
+                        span.shrink_to_hi(),
+                        &format!(
+                            "consider borrowing the value inside the `{}` with `{}`",
+                            wrapper, method,
+                        ),
+                        format!(".{}()", method),
+                        Applicability::MaybeIncorrect,
+                    );
+                    err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Borrow);
+                    return;
+                }
+            }
+        }
+
+        let inner_ty = substs.type_at(0);
+        if holds(inner_ty) || holds(tcx.mk_imm_ref(tcx.lifetimes.re_static, inner_ty)) {
+            err.help(&format!(
+                "the trait `{}` is implemented for `{}`, the type inside the `{}`; consider \
+                 matching on the `{}` to reach the value inside it",
+                trait_ref.print_only_trait_path(),
+                inner_ty,
+                wrapper,
+                wrapper,
+            ));
+        }
+    }
+
+    /// Traits are often implemented for `&[T]` or `Vec<T>` but not for arrays. When a bound doesn't
+    /// hold for an array argument but would for one of those, suggest slicing the array or, if its
+    /// elements can be cloned, turning it into a `Vec`.
+    fn suggest_slice_or_vec_for_array(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let span = obligation.cause.span;
+        if !points_at_arg || span.from_expansion() {
+            return;
+        }
+        let tcx = self.tcx;
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        // Slicing or copying the array would only sidestep these, not help.
+        let lang_items = tcx.lang_items();
+        if [lang_items.copy_trait(), lang_items.clone_trait(), lang_items.sized_trait()]
+            .contains(&Some(trait_ref.def_id()))
+            || tcx.trait_is_auto(trait_ref.def_id())
+        {
+            return;
+        }
+        let elem_ty = match trait_ref.self_ty().no_bound_vars().map(|ty| ty.kind()) {
+            Some(&ty::Array(elem_ty, _)) if !elem_ty.has_infer_types_or_consts() => elem_ty,
+            _ => return,
+        };
+        let holds = |ty| {
+            let obligation =
+                self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, ty);
+            self.predicate_may_hold(&obligation)
+        };
+        // Both indexing and `.to_vec()` bind tighter than a dereference, a cast or a binary
+        // operator, so `*boxed` becomes `&(*boxed)[..]` rather than `&*boxed[..]`.
+        let (open, close) = match cause_expr(tcx.hir(), &obligation.cause) {
+            Some(arg) if arg.precedence().order() >= PREC_POSTFIX => ("", ""),
+            _ => ("(", ")"),
+        };
+
+        let slice_ty = tcx.mk_imm_ref(tcx.lifetimes.re_static, tcx.mk_slice(elem_ty));
+        if holds(slice_ty) {
+            let suggestions_len = err.suggestions.len();
+            err.multipart_suggestion(
+                &format!("consider borrowing the array as a slice, `&[{}]`", elem_ty),
+                vec![
+                    (span.shrink_to_lo(), format!("&{}", open)),
+                    (span.shrink_to_hi(), format!("{}[..]", close)),
+                ],
+                Applicability::MaybeIncorrect,
+            );
+            err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+            return;
+        }
+
+        // `to_vec` clones the elements into the new `Vec`.
+        let elem_is_clone = lang_items.clone_trait().map_or(false, |clone_trait| {
+            let trait_ref = ty::TraitRef::new(clone_trait, tcx.mk_substs_trait(elem_ty, &[]));
+            let obligation = Obligation::new(
+                obligation.cause.clone(),
+                obligation.param_env,
+                trait_ref.without_const().to_predicate(tcx),
+            );
+            self.predicate_must_hold_modulo_regions(&obligation)
+        });
+        if !elem_is_clone {
+            return;
+        }
+        if let Some(vec_ty) = tcx.mk_diagnostic_item(elem_ty, sym::vec_type) {
+            if holds(vec_ty) {
+                let mut parts = vec![(span.shrink_to_hi(), format!("{}.to_vec()", close))];
+                if !open.is_empty() {
+                    parts.insert(0, (span.shrink_to_lo(), open.to_string()));
+                }
+                let suggestions_len = err.suggestions.len();
+                err.multipart_suggestion(
+                    &format!("consider copying the array into a vector, `Vec<{}>`", elem_ty),
+                    parts,
+                    Applicability::MaybeIncorrect,
+                );
+                err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+            }
+        }
+    }
+
+    /// Whenever references are used by mistake, like `for (i, e) in &vec.iter().enumerate()`,
+    /// suggest removing these references until we reach a type that implements the trait.
+    ///
+    /// Returns `true` if the suggestion was made.
+    fn suggest_remove_reference(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        has_custom_message: bool,
+    ) -> bool {
+        let span = obligation.cause.span;
+
+        if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
+            let refs_number =
+                snippet.chars().filter(|c| !c.is_whitespace()).take_while(|c| *c == '&').count();
+            if let Some('\'') = snippet.chars().filter(|c| !c.is_whitespace()).nth(refs_number) {
+                // Do not suggest removal of borrow from type arguments.
+                return false;
+            }
+
+            let mut suggested_ty = match trait_ref.self_ty().no_bound_vars() {
+                Some(ty) => ty,
+                None => return false,
+            };
+
+            for refs_remaining in 0..refs_number {
+                if let ty::Ref(_, inner_ty, _) = suggested_ty.kind() {
+                    suggested_ty = inner_ty;
+
+                    let new_obligation = self.mk_trait_obligation_with_new_self_ty(
+                        obligation.param_env,
+                        trait_ref,
+                        suggested_ty,
+                    );
+
+                    // Don't add to a curated `#[rustc_on_unimplemented]` message unless we're
+                    // sure that the suggestion applies.
+                    let holds = if has_custom_message {
+                        self.predicate_must_hold_modulo_regions(&new_obligation)
+                    } else {
+                        self.predicate_may_hold(&new_obligation)
+                    };
+                    if holds {
+                        let sp = self
+                            .tcx
+                            .sess
+                            .source_map()
+                            .span_take_while(span, |c| c.is_whitespace() || *c == '&');
+
+                        let remove_refs = refs_remaining + 1;
+
+                        let msg = if remove_refs == 1 {
+                            "consider removing the leading `&`-reference".to_string()
+                        } else {
+                            format!("consider removing {} leading `&`-references", remove_refs)
+                        };
+                        // Without a custom message we settle for the bound possibly holding.
+                        let applicability = if has_custom_message
+                            || self.predicate_must_hold_modulo_regions(&new_obligation)
+                        {
+                            Applicability::MachineApplicable
+                        } else {
+                            Applicability::MaybeIncorrect
                         };
 
-                        err.span_suggestion_short(
-                            sp,
-                            &msg,
-                            String::new(),
-                            Applicability::MachineApplicable,
+                        let suggestions_len = err.suggestions.len();
+                        err.span_suggestion_short(sp, &msg, String::new(), applicability);
+                        err.categorize_suggestions_since(
+                            suggestions_len,
+                            SuggestionCategory::RemoveReference,
                         );
-                        break;
+                        return true;
                     }
                 } else {
                     break;
                 }
             }
         }
+        false
+    }
+
+    /// When the iteratee of a `for` loop doesn't implement `IntoIterator` but has an inherent
+    /// `iter`, `iter_mut` or `into_iter` method, suggest calling the one that matches the way the
+    /// iteratee is being borrowed: `for x in &mut foo` → `for x in foo.iter_mut()`.
+    ///
+    /// This should only be called when `suggest_remove_reference` didn't already provide a
+    /// suggestion for the same span.
+    fn suggest_iteration_method(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let span = obligation.cause.span;
+        if !matches!(span.desugaring_kind(), Some(DesugaringKind::ForLoop(_))) {
+            return;
+        }
+        // Types that aren't iterators fail the `I: Iterator` bound of the blanket
+        // `impl<I: Iterator> IntoIterator for I` rather than the `IntoIterator` bound itself.
+        let into_iterator = self.tcx.get_diagnostic_item(sym::IntoIterator);
+        let trait_ref = match &obligation.cause.code {
+            ObligationCauseCode::ImplDerivedObligation(data)
+                if Some(data.parent_trait_ref.def_id()) == into_iterator =>
+            {
+                data.parent_trait_ref
+            }
+            _ if Some(trait_ref.def_id()) == into_iterator => trait_ref,
+            _ => return,
+        };
+        let self_ty = match self.resolve_vars_if_possible(trait_ref.self_ty()).no_bound_vars() {
+            Some(ty) => ty,
+            None => return,
+        };
+        // `&mut x` wants to mutate the elements, `&x` only wants to look at them and `x` can
+        // give up ownership of them, so we try the methods in that order of preference.
+        let (iteratee_ty, mutability, candidates) = match *self_ty.kind() {
+            ty::Ref(_, ty, hir::Mutability::Mut) => {
+                (ty, Some(hir::Mutability::Mut), &[sym::iter_mut, sym::iter][..])
+            }
+            ty::Ref(_, ty, hir::Mutability::Not) => {
+                (ty, Some(hir::Mutability::Not), &[sym::iter][..])
+            }
+            _ => (self_ty, None, &[sym::into_iter, sym::iter, sym::iter_mut][..]),
+        };
+        let adt_did = match iteratee_ty.kind() {
+            ty::Adt(def, _) => def.did,
+            _ => return,
+        };
+        let method = match candidates.iter().find(|&&name| {
+            self.tcx.inherent_impls(adt_did).iter().any(|&impl_did| {
+                self.tcx
+                    .associated_items(impl_did)
+                    .filter_by_name_unhygienic(name)
+                    .any(|item| item.kind == ty::AssocKind::Fn && item.fn_has_self_parameter)
+            })
+        }) {
+            Some(method) => method,
+            None => return,
+        };
+
+        let snippet = match self.tcx.sess.source_map().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        // Remove the explicit borrow, as the method will take care of it.
+        let expr = match mutability {
+            Some(hir::Mutability::Mut) => snippet
+                .strip_prefix('&')
+                .and_then(|s| s.trim_start().strip_prefix("mut "))
+                .map_or(&snippet[..], |s| s.trim_start()),
+            Some(hir::Mutability::Not) => snippet.strip_prefix('&').map_or(&snippet[..], |s| s),
+            None => &snippet[..],
+        };
+        let borrow_len = snippet.len() - expr.len();
+        let mut sugg = vec![(span.shrink_to_hi(), format!(".{}()", method))];
+        if borrow_len > 0 {
+            sugg.push((span.with_hi(span.lo() + BytePos(borrow_len as u32)), String::new()));
+        }
+        let suggestions_len = err.suggestions.len();
+        err.multipart_suggestion(
+            &format!("consider iterating using `{}::{}`", self.tcx.def_path_str(adt_did), method),
+            sugg,
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
     }
 
     /// Check if the trait bound is implemented for a different mutability and note it in the
@@ -795,7 +2147,12 @@ fn suggest_change_mut(
         err: &mut DiagnosticBuilder<'_>,
         trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
         points_at_arg: bool,
+        has_custom_message: bool,
     ) {
+        // Reborrowing the mutable reference was already suggested instead.
+        if points_at_arg && self.mut_reborrow_of_arg(obligation, trait_ref).is_some() {
+            return;
+        }
         let span = obligation.cause.span;
         if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
             let refs_number =
@@ -840,26 +2197,364 @@ fn suggest_change_mut(
                         .sess
                         .source_map()
                         .span_take_while(span, |c| c.is_whitespace() || *c == '&');
-                    if points_at_arg && mutability == hir::Mutability::Not && refs_number > 0 {
-                        err.span_suggestion_verbose(
-                            sp,
-                            "consider changing this borrow's mutability",
-                            "&mut ".to_string(),
-                            Applicability::MachineApplicable,
-                        );
-                    } else {
-                        err.note(&format!(
-                            "`{}` is implemented for `{:?}`, but not for `{:?}`",
-                            trait_ref.print_only_trait_path(),
-                            suggested_ty,
-                            trait_ref.skip_binder().self_ty(),
-                        ));
+                    let mut_borrow =
+                        if points_at_arg && mutability == hir::Mutability::Not && refs_number > 0 {
+                            mut_borrow_of_arg(self, obligation)
+                        } else {
+                            MutBorrow::Impossible
+                        };
+                    let suggestions_len = err.suggestions.len();
+                    match mut_borrow {
+                        MutBorrow::Allowed => {
+                            err.span_suggestion_verbose(
+                                sp,
+                                "consider changing this borrow's mutability",
+                                "&mut ".to_string(),
+                                Applicability::MachineApplicable,
+                            );
+                        }
+                        MutBorrow::NeedsMutBinding(binding_span, name) => {
+                            let parts = vec![
+                                (binding_span.shrink_to_lo(), "mut ".to_string()),
+                                (sp, "&mut ".to_string()),
+                            ];
+                            err.multipart_suggestion(
+                                &format!(
+                                    "consider changing this borrow's mutability and declaring \
+                                     `{}` as mutable",
+                                    name,
+                                ),
+                                parts,
+                                Applicability::MachineApplicable,
+                            );
+                        }
+                        MutBorrow::Impossible if !has_custom_message => {
+                            err.note(&format!(
+                                "`{}` is implemented for `{:?}`, but not for `{:?}`",
+                                trait_ref.print_only_trait_path(),
+                                suggested_ty,
+                                trait_ref.skip_binder().self_ty(),
+                            ));
+                        }
+                        MutBorrow::Impossible => {}
                     }
+                    err.categorize_suggestions_since(
+                        suggestions_len,
+                        SuggestionCategory::ChangeMut,
+                    );
                 }
             }
         }
     }
 
+    /// When the argument is a `&mut T` that only reaches the bound as a shared reference, either
+    /// because it was written as `&x` or because it got coerced, and the bound holds for the
+    /// `&mut T` itself, suggest reborrowing it: `bar(&x)` → `bar(&mut *x)`. Unlike
+    /// `suggest_change_mut`, this doesn't borrow the reference itself, and unlike
+    /// `suggest_remove_reference`, it doesn't move it.
+    fn suggest_mut_reborrow(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) -> bool {
+        if !points_at_arg {
+            return false;
+        }
+        let sugg = match self.mut_reborrow_of_arg(obligation, trait_ref) {
+            Some(sugg) => sugg,
+            None => return false,
+        };
+        let span = obligation.cause.span;
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            span,
+            "consider reborrowing the mutable reference",
+            sugg,
+            Applicability::MachineApplicable,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Borrow);
+        true
+    }
+
+    /// Finds the argument the obligation points at and, if it is a place holding a `&mut T` for
+    /// which the bound holds while the obligation is about a shared reference, returns the
+    /// `&mut *place` that reborrows it.
+    fn mut_reborrow_of_arg(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) -> Option<String> {
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        if trait_ref.has_infer_types_or_consts() {
+            return None;
+        }
+        match trait_ref.self_ty().no_bound_vars()?.kind() {
+            ty::Ref(_, _, hir::Mutability::Not) => {}
+            _ => return None,
+        }
+        let hir = self.tcx.hir();
+        let arg = cause_expr(hir, &obligation.cause)?;
+        let place = match arg.kind {
+            hir::ExprKind::AddrOf(hir::BorrowKind::Ref, hir::Mutability::Not, place) => place,
+            _ => arg,
+        };
+        let typeck_results = self.in_progress_typeck_results?.borrow();
+        // The type before any adjustment, so that a `&mut T` coerced to `&T` is seen as such.
+        let place_ty = typeck_results.expr_ty_opt(place)?;
+        if !matches!(place_ty.kind(), ty::Ref(_, _, hir::Mutability::Mut))
+            || place_ty.has_escaping_bound_vars()
+        {
+            return None;
+        }
+        // Reborrowing a local doesn't need it to be `mut`, but a field has to be reachable
+        // through `&mut` or a `mut` binding.
+        let reborrowable = match place.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => {
+                matches!(path.res, Res::Local(_))
+            }
+            hir::ExprKind::Field(..) => matches!(
+                mut_borrow_of_place(hir, Some(&*typeck_results), place),
+                MutBorrow::Allowed
+            ),
+            _ => false,
+        };
+        if !reborrowable {
+            return None;
+        }
+        let new_obligation =
+            self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, place_ty);
+        if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+            return None;
+        }
+        let snippet = self.tcx.sess.source_map().span_to_snippet(place.span).ok()?;
+        Some(format!("&mut *{}", snippet))
+    }
+
+    /// When a container can't be indexed by a value but can by a reference to it, like a
+    /// `HashMap<String, V>` indexed by a `String`, suggest borrowing the index operand:
+    /// `map[key]` → `map[&key]`.
+    fn suggest_borrowing_index(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let tcx = self.tcx;
+        if Some(trait_ref.def_id()) != tcx.lang_items().index_trait() {
+            return;
+        }
+        let trait_ref = match self.resolve_vars_if_possible(trait_ref).no_bound_vars() {
+            Some(trait_ref) if !trait_ref.has_infer_types_or_consts() => trait_ref,
+            _ => return,
+        };
+        let index_ty = trait_ref.substs.type_at(1);
+        if index_ty.is_ref() || index_ty.references_error() {
+            return;
+        }
+        let index = cause_expr(tcx.hir(), &obligation.cause).and_then(|expr| match expr.kind {
+            hir::ExprKind::Index(_, index) => Some(index),
+            _ => None,
+        });
+        let index = match index {
+            Some(index) if !index.span.from_expansion() => index,
+            _ => return,
+        };
+        // Don't stack a second `&` on top of a suggestion that already edits the index operand.
+        let span = index.span.shrink_to_lo();
+        let already_suggested = err.suggestions.iter().any(|suggestion| {
+            suggestion.substitutions.iter().any(|substitution| {
+                substitution.parts.iter().any(|part| part.span.lo() == span.lo())
+            })
+        });
+        if already_suggested {
+            return;
+        }
+        let borrowed_trait_ref = ty::TraitRef::new(
+            trait_ref.def_id,
+            tcx.mk_substs_trait(
+                trait_ref.self_ty(),
+                &[tcx.mk_imm_ref(tcx.lifetimes.re_erased, index_ty).into()],
+            ),
+        );
+        let new_obligation = Obligation::new(
+            ObligationCause::dummy(),
+            obligation.param_env,
+            borrowed_trait_ref.without_const().to_predicate(tcx),
+        );
+        if !self.predicate_must_hold_modulo_regions(&new_obligation) {
+            return;
+        }
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            span,
+            "consider borrowing the index",
+            "&".to_string(),
+            Applicability::MachineApplicable,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Borrow);
+    }
+
+    /// When two type arguments of a generic type are written the wrong way around, like
+    /// `HashMap<V, K>`, the bound that fails is one that the other argument would have met. If
+    /// swapping the two arguments of the type written in the function makes the requirement hold,
+    /// suggest doing so.
+    fn suggest_swapping_type_params(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        // The type with the transposed arguments is the `Self` of either the failing bound or of
+        // one of the requirements that led to it.
+        let mut trait_refs = vec![trait_ref];
+        let mut code = &obligation.cause.code;
+        while let ObligationCauseCode::BuiltinDerivedObligation(data)
+        | ObligationCauseCode::ImplDerivedObligation(data)
+        | ObligationCauseCode::DerivedObligation(data) = code
+        {
+            trait_refs.push(data.parent_trait_ref);
+            code = &*data.parent_code;
+        }
+        let swap = trait_refs.into_iter().find_map(|trait_ref| {
+            let trait_ref = self.resolve_vars_if_possible(trait_ref);
+            if trait_ref.has_infer_types_or_consts()
+                || trait_ref.skip_binder().has_escaping_bound_vars()
+            {
+                return None;
+            }
+            let (adt, substs) = match *trait_ref.skip_binder().self_ty().kind() {
+                ty::Adt(adt, substs) => (adt, substs),
+                _ => return None,
+            };
+            let type_indices: Vec<usize> = substs
+                .iter()
+                .enumerate()
+                .filter(|(_, arg)| matches!(arg.unpack(), GenericArgKind::Type(_)))
+                .map(|(i, _)| i)
+                .collect();
+            let mut swaps = (0..type_indices.len())
+                .flat_map(|a| (a + 1..type_indices.len()).map(move |b| (a, b)))
+                .filter(|&(a, b)| {
+                    let (i, j) = (type_indices[a], type_indices[b]);
+                    if substs[i] == substs[j] {
+                        return false;
+                    }
+                    let mut swapped = substs.to_vec();
+                    swapped.swap(i, j);
+                    let new_self_ty = tcx.mk_adt(adt, tcx.intern_substs(&swapped));
+                    let new_obligation = self.mk_trait_obligation_with_new_self_ty(
+                        obligation.param_env,
+                        trait_ref,
+                        new_self_ty,
+                    );
+                    self.predicate_must_hold_modulo_regions(&new_obligation)
+                });
+            // Only suggest a swap when it is the only one that works.
+            match (swaps.next(), swaps.next()) {
+                (Some(swap), None) => Some((adt.did, swap)),
+                _ => None,
+            }
+        });
+        let (adt_def_id, (a, b)) = match swap {
+            Some(swap) => swap,
+            None => return,
+        };
+
+        let hir = tcx.hir();
+        let body_owner = hir.get_parent_node(obligation.cause.body_id);
+        let mut visitor = AdtPathVisitor { def_id: adt_def_id, args: vec![] };
+        if let Some(decl) = hir.fn_decl_by_hir_id(body_owner) {
+            hir::intravisit::walk_fn_decl(&mut visitor, decl);
+        }
+        if let Some(body) = cause_body(hir, &obligation.cause) {
+            visitor.visit_body(body);
+        }
+        // When the type is written more than once, it isn't clear which one is wrong.
+        let args = match &visitor.args[..] {
+            [args] => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    hir::GenericArg::Type(ty) => Some(ty),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            _ => return,
+        };
+        let (first, second) = match (args.get(a), args.get(b)) {
+            (Some(first), Some(second)) => (first, second),
+            _ => return,
+        };
+        let source_map = tcx.sess.source_map();
+        let (first_snippet, second_snippet) =
+            match (source_map.span_to_snippet(first.span), source_map.span_to_snippet(second.span))
+            {
+                (Ok(first), Ok(second)) => (first, second),
+                _ => return,
+            };
+        let suggestions_len = err.suggestions.len();
+        err.multipart_suggestion(
+            &format!(
+                "the type arguments of `{}` may be the wrong way around",
+                tcx.def_path_str(adt_def_id),
+            ),
+            vec![(first.span, second_snippet), (second.span, first_snippet)],
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::SwapTypeArgs);
+    }
+
+    /// When a trait object of `Trait` (behind a reference or a `Box`) is passed where a
+    /// generic function requires `T: Trait`, the user most likely wants dynamic dispatch, for
+    /// example to handle a collection of heterogeneous types. Point out that the function could
+    /// take the trait object directly, as long as `Trait` is object safe.
+    fn suggest_dyn_trait_parameter(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let (item_def_id, bound_span) = match obligation.cause.code {
+            ObligationCauseCode::BindingObligation(item_def_id, span) => (item_def_id, span),
+            _ => return,
+        };
+        if !matches!(self.tcx.def_kind(item_def_id), DefKind::Fn | DefKind::AssocFn) {
+            return;
+        }
+        let self_ty = match self.resolve_vars_if_possible(trait_ref.self_ty()).no_bound_vars() {
+            Some(ty) => ty,
+            None => return,
+        };
+        let pointee = match *self_ty.kind() {
+            ty::Ref(_, ty, _) => ty,
+            ty::Adt(def, _) if def.is_box() => self_ty.boxed_ty(),
+            _ => return,
+        };
+        let trait_def_id = trait_ref.def_id();
+        match pointee.kind() {
+            ty::Dynamic(preds, _) if preds.principal_def_id() == Some(trait_def_id) => {}
+            _ => return,
+        }
+        if !self.tcx.object_safety_violations(trait_def_id).is_empty() {
+            return;
+        }
+        let trait_path = self.tcx.def_path_str(trait_def_id);
+        err.span_help(
+            bound_span,
+            &format!(
+                "if `{}` is meant to accept values of different types that implement `{}`, \
+                 consider taking `&dyn {}` or `Box<dyn {}>` instead of a generic parameter",
+                self.tcx.def_path_str(item_def_id),
+                trait_path,
+                trait_path,
+                trait_path,
+            ),
+        );
+    }
+
     fn suggest_semicolon_removal(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -877,23 +2572,404 @@ fn suggest_semicolon_removal(
             kind: hir::ItemKind::Fn(sig, _, body_id), ..
         })) = node
         {
-            let body = hir.body(*body_id);
-            if let hir::ExprKind::Block(blk, _) = &body.value.kind {
-                if sig.decl.output.span().overlaps(span)
-                    && blk.expr.is_none()
-                    && is_empty_tuple(trait_ref.self_ty())
-                {
-                    // FIXME(estebank): When encountering a method with a trait
-                    // bound not satisfied in the return type with a body that has
-                    // no return, suggest removal of semicolon on last statement.
-                    // Once that is added, close #54771.
-                    if let Some(ref stmt) = blk.stmts.last() {
-                        let sp = self.tcx.sess.source_map().end_point(stmt.span);
-                        err.span_label(sp, "consider removing this semicolon");
-                    }
-                }
+            let body = hir.body(*body_id);
+            if let hir::ExprKind::Block(blk, _) = &body.value.kind {
+                if sig.decl.output.span().overlaps(span)
+                    && blk.expr.is_none()
+                    && is_empty_tuple(trait_ref.self_ty())
+                {
+                    // FIXME(estebank): When encountering a method with a trait
+                    // bound not satisfied in the return type with a body that has
+                    // no return, suggest removal of semicolon on last statement.
+                    // Once that is added, close #54771.
+                    if let Some(ref stmt) = blk.stmts.last() {
+                        let sp = self.tcx.sess.source_map().end_point(stmt.span);
+                        err.span_label(sp, "consider removing this semicolon");
+                    }
+                }
+            }
+        }
+    }
+
+    /// When a tuple element other than the last one has to be `Sized` but isn't, suggest moving
+    /// it to the end of the tuple or boxing it: `(str, u8)` → `(u8, str)` or `(Box<str>, u8)`.
+    fn suggest_moving_unsized_tuple_elem(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+    ) {
+        let tcx = self.tcx;
+        if !matches!(obligation.cause.code, ObligationCauseCode::TupleElem)
+            || Some(trait_ref.def_id()) != tcx.lang_items().sized_trait()
+        {
+            return;
+        }
+        let self_ty = self.resolve_vars_if_possible(trait_ref.skip_binder().self_ty());
+
+        // The obligation points at the whole type the tuple is part of, so look for the tuple
+        // within it, either in the signature of an item or in a body.
+        let hir = tcx.hir();
+        let mut visitor =
+            UnsizedTupleElemVisitor { tcx, span: obligation.cause.span, ty: self_ty, found: None };
+        match hir.find(obligation.cause.body_id) {
+            Some(Node::Item(item)) => visitor.visit_item(item),
+            Some(Node::TraitItem(item)) => visitor.visit_trait_item(item),
+            Some(Node::ImplItem(item)) => visitor.visit_impl_item(item),
+            _ => {
+                if let Some(body) = cause_body(hir, &obligation.cause) {
+                    visitor.visit_body(body);
+                }
+            }
+        }
+        let (elems, idx) = match visitor.found {
+            Some(found) => found,
+            None => return,
+        };
+        let elem = &elems[idx];
+        let snippet = match tcx.sess.source_map().span_to_snippet(elem.span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        // `idx` is never the last element, so there is always one after it.
+        let last = &elems[elems.len() - 1];
+        err.multipart_suggestion(
+            "consider moving the unsized element to the end of the tuple",
+            vec![
+                (elem.span.until(elems[idx + 1].span), String::new()),
+                (last.span.shrink_to_hi(), format!(", {}", snippet)),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+        err.multipart_suggestion(
+            "or consider boxing it so that it has a statically known size",
+            vec![
+                (elem.span.shrink_to_lo(), "Box<".to_string()),
+                (elem.span.shrink_to_hi(), ">".to_string()),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// Suggest deriving `Clone`, `Copy` or `Default` for a local type that doesn't implement it,
+    /// along with `Clone` if `Copy` needs it too, or point at the fields that prevent the derive.
+    /// A type parameter can't be annotated, so it is restricted instead, as are the parameters
+    /// that the derived impl would need to implement the trait. Returns whether the self type of
+    /// `trait_ref` was such a type parameter.
+    fn suggest_derive(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let trait_def_id = trait_ref.def_id();
+        let clone_trait = tcx.lang_items().clone_trait();
+        let is_clone = Some(trait_def_id) == clone_trait;
+        let is_copy = Some(trait_def_id) == tcx.lang_items().copy_trait();
+        let is_default = Some(trait_def_id) == tcx.get_diagnostic_item(sym::Default);
+        if !is_clone && !is_copy && !is_default {
+            return false;
+        }
+        let self_ty = match trait_ref.self_ty().no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() => ty,
+            _ => return false,
+        };
+        let (adt, substs) = match *self_ty.kind() {
+            ty::Adt(adt, substs) if adt.did.is_local() => (adt, substs),
+            ty::Param(_) => {
+                self.suggest_restricting_param_bound(err, trait_ref, obligation);
+                return true;
+            }
+            _ => return false,
+        };
+        // A type with a destructor can't be `Copy`, a union can only be `Clone` if it is `Copy`
+        // too, and `Default` can only be derived for structs.
+        if (is_copy && tcx.adt_destructor(adt.did).is_some())
+            || (is_clone && adt.is_union())
+            || (is_default && !adt.is_struct())
+        {
+            return false;
+        }
+        // A type that already has an impl of the trait that doesn't apply here wouldn't be helped
+        // by another one.
+        let mut has_impl = false;
+        tcx.for_each_relevant_impl(trait_def_id, self_ty, |_| has_impl = true);
+        if has_impl {
+            return false;
+        }
+
+        let implements = |trait_def_id, ty| {
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs_trait(ty, &[]));
+            let obligation = Obligation::new(
+                obligation.cause.clone(),
+                obligation.param_env,
+                trait_ref.without_const().to_predicate(tcx),
+            );
+            self.predicate_must_hold_modulo_regions(&obligation)
+        };
+        let adt_name = tcx.item_name(adt.did);
+        let trait_name = tcx.item_name(trait_def_id);
+        // A field whose type is a type parameter doesn't prevent the derive: the derived impl
+        // requires the parameter to implement the trait, which it can be restricted to.
+        let (param_fields, blocking_fields): (Vec<_>, Vec<_>) = adt
+            .all_fields()
+            .filter(|field| !implements(trait_def_id, field.ty(tcx, substs)))
+            .partition(|field| matches!(field.ty(tcx, substs).kind(), ty::Param(_)));
+        match &blocking_fields[..] {
+            [] => {}
+            [field] => {
+                err.span_note(
+                    tcx.def_span(field.did),
+                    &format!(
+                        "`{}` can't derive `{}` because the type of this field, `{}`, doesn't \
+                         implement `{}`",
+                        adt_name,
+                        trait_name,
+                        field.ty(tcx, substs),
+                        trait_name,
+                    ),
+                );
+                return false;
+            }
+            fields => {
+                let mut spans = MultiSpan::from_spans(
+                    fields.iter().map(|field| tcx.def_span(field.did)).collect(),
+                );
+                for field in fields {
+                    spans.push_span_label(
+                        tcx.def_span(field.did),
+                        format!("`{}` doesn't implement `{}`", field.ty(tcx, substs), trait_name),
+                    );
+                }
+                err.span_note(
+                    spans,
+                    &format!(
+                        "`{}` can't derive `{}` because the types of these fields don't \
+                         implement it",
+                        adt_name, trait_name,
+                    ),
+                );
+                return false;
+            }
+        }
+
+        let derives = if is_copy && !clone_trait.map_or(true, |clone| implements(clone, self_ty)) {
+            "Copy, Clone".to_string()
+        } else {
+            trait_name.to_string()
+        };
+        // Put the attribute on its own line, above the line the type's definition starts on.
+        let span = tcx.def_span(adt.did);
+        let line = match tcx.sess.source_map().span_to_prev_source(span) {
+            Ok(prev_source) => prev_source.rsplit('\n').next().unwrap_or_default().to_string(),
+            Err(_) => return false,
+        };
+        let indentation = &line[..line.len() - line.trim_start().len()];
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            span.with_lo(span.lo() - BytePos(line.len() as u32)).shrink_to_lo(),
+            &format!("consider annotating `{}` with `#[derive({})]`", adt_name, derives),
+            format!("{}#[derive({})]\n", indentation, derives),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Derive);
+        let mut params = vec![];
+        for field in param_fields {
+            let param = field.ty(tcx, substs);
+            if params.contains(&param) {
+                continue;
+            }
+            params.push(param);
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs_trait(param, &[]));
+            self.suggest_restricting_param_bound(err, ty::Binder::dummy(trait_ref), obligation);
+        }
+        false
+    }
+
+    /// When a local trait isn't implemented for a local type and nothing else was suggested,
+    /// suggest adding the `impl` that would be needed after the type, with the trait's required
+    /// items stubbed out. This is only suggested once for each type and trait.
+    fn suggest_impl_for_local_type(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let trait_def_id = trait_ref.def_id();
+        if !err.suggestions.is_empty()
+            || !trait_def_id.is_local()
+            || tcx.trait_is_auto(trait_def_id)
+            // The stubbed items would have to refer to the trait's parameters.
+            || tcx.generics_of(trait_def_id).count() > 1
+        {
+            return;
+        }
+        let self_ty = match trait_ref.self_ty().no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() && !ty.has_param_types_or_consts() => ty,
+            _ => return,
+        };
+        let adt = match self_ty.kind() {
+            ty::Adt(adt, _) if adt.did.is_local() => *adt,
+            _ => return,
+        };
+        // An `impl` for the type, or a reference to it, that doesn't apply here is too restrictive
+        // or for the wrong type, and a blanket `impl` has bounds the type doesn't meet. Another
+        // `impl` wouldn't fix either, and might well conflict.
+        let has_impl = tcx.all_impls(trait_def_id).any(|impl_def_id| {
+            let impl_self_ty = tcx.type_of(impl_def_id).peel_refs();
+            matches!(impl_self_ty.kind(), ty::Param(_)) || impl_self_ty.ty_adt_def() == Some(adt)
+        });
+        if has_impl {
+            return;
+        }
+        let item_span = match tcx.hir().span_if_local(adt.did) {
+            Some(span) if !span.from_expansion() => span,
+            _ => return,
+        };
+
+        let hir = tcx.hir();
+        let sm = tcx.sess.source_map();
+        let indentation = " ".repeat(sm.span_to_margin(item_span).unwrap_or(0));
+        let mut items = vec![];
+        let mut has_placeholders = false;
+        for item in tcx.associated_items(trait_def_id).in_definition_order() {
+            let trait_item = match hir.get_if_local(item.def_id) {
+                Some(Node::TraitItem(trait_item)) => trait_item,
+                _ => return,
+            };
+            let stub = match trait_item.kind {
+                hir::TraitItemKind::Const(ty, None) => match sm.span_to_snippet(ty.span) {
+                    Ok(ty) => {
+                        has_placeholders = true;
+                        format!("const {}: {} = /* value */;", item.ident, ty)
+                    }
+                    Err(_) => return,
+                },
+                hir::TraitItemKind::Fn(_, hir::TraitFn::Required(_)) => {
+                    match sm.span_to_snippet(trait_item.span) {
+                        Ok(sig) => format!("{} {{ todo!() }}", sig.trim_end_matches(';')),
+                        Err(_) => return,
+                    }
+                }
+                hir::TraitItemKind::Type(_, None) => {
+                    has_placeholders = true;
+                    format!("type {} = /* type */;", item.ident)
+                }
+                _ => continue,
+            };
+            // Long signatures split across lines would come out mangled.
+            if stub.contains('\n') {
+                return;
+            }
+            items.push(format!("\n{}    {}", indentation, stub));
+        }
+        let body = if items.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{{}\n{}}}", items.concat(), indentation)
+        };
+        let trait_path = trait_ref.print_only_trait_path();
+        let msg = format!("consider implementing `{}` for `{}`", trait_path, self_ty);
+        // Every error about the type not implementing the trait would otherwise repeat the same
+        // `impl`.
+        let suggestion_id = (DiagnosticMessageId::ErrorId(277), Some(item_span), msg.clone());
+        if !tcx.sess.one_time_diagnostics.borrow_mut().insert(suggestion_id) {
+            return;
+        }
+        err.span_suggestion_verbose(
+            item_span.shrink_to_hi(),
+            &msg,
+            format!("\n\n{}impl {} for {} {}", indentation, trait_path, self_ty, body),
+            if has_placeholders {
+                Applicability::HasPlaceholders
+            } else {
+                Applicability::MaybeIncorrect
+            },
+        );
+    }
+
+    /// When an `Option` or a `Result` of a collection is passed where an `IntoIterator` of the
+    /// collection's items is expected, suggest flattening it: `opt_vec.into_iter().flatten()`.
+    fn suggest_flatten_nested_collection(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        predicate: ty::PolyProjectionPredicate<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let predicate = match self.resolve_vars_if_possible(predicate).no_bound_vars() {
+            Some(predicate) => predicate,
+            None => return,
+        };
+        let item_def_id = predicate.projection_ty.item_def_id;
+        let into_iterator = tcx.get_diagnostic_item(sym::IntoIterator);
+        if into_iterator.is_none()
+            || tcx.trait_of_item(item_def_id) != into_iterator
+            || tcx.item_name(item_def_id) != sym::Item
+        {
+            return;
+        }
+        let self_ty = predicate.projection_ty.self_ty();
+        let collection_ty = match self_ty.kind() {
+            ty::Adt(def, substs)
+                if tcx.is_diagnostic_item(sym::option_type, def.did)
+                    || tcx.is_diagnostic_item(sym::result_type, def.did) =>
+            {
+                substs.type_at(0)
             }
+            _ => return,
+        };
+        if predicate.ty.has_infer_types_or_consts() || collection_ty.has_infer_types_or_consts() {
+            return;
+        }
+        // Flattening only helps if the wrapped collection yields the expected items.
+        let flattened = ty::ProjectionPredicate {
+            projection_ty: ty::ProjectionTy {
+                substs: tcx.mk_substs_trait(collection_ty, &[]),
+                item_def_id,
+            },
+            ty: predicate.ty,
+        };
+        let flattened_obligation = Obligation::new(
+            obligation.cause.clone(),
+            obligation.param_env,
+            ty::Binder::dummy(flattened).to_predicate(tcx),
+        );
+        if !self.predicate_may_hold(&flattened_obligation) {
+            return;
         }
+
+        // The obligation points at the callee, look for the argument that has the nested type.
+        let typeck_results = match self.in_progress_typeck_results {
+            Some(typeck_results) => typeck_results.borrow(),
+            None => return,
+        };
+        let body = match cause_body(tcx.hir(), &obligation.cause) {
+            Some(body) => body,
+            None => return,
+        };
+        let mut visitor = CallArgsVisitor { callee_span: obligation.cause.span, args: &[] };
+        visitor.visit_body(body);
+        let arg = match visitor.args.iter().find(|arg| {
+            typeck_results
+                .expr_ty_adjusted_opt(arg)
+                .map_or(false, |ty| self.resolve_vars_if_possible(ty) == self_ty)
+        }) {
+            Some(arg) => arg,
+            None => return,
+        };
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            arg.span.shrink_to_hi(),
+            &format!(
+                "consider flattening the `{}` to iterate over its `{}` items",
+                self_ty, predicate.ty
+            ),
+            ".into_iter().flatten()".to_owned(),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
     }
 
     fn return_type_span(&self, obligation: &PredicateObligation<'tcx>) -> Option<Span> {
@@ -949,6 +3025,13 @@ fn suggest_impl_trait(
             // For example, `fn foo() -> str` needs to be filtered out.
             _ => return false,
         };
+        let is_future = match ty.kind() {
+            ty::Dynamic(predicates, _) => {
+                predicates.principal_def_id().is_some()
+                    && predicates.principal_def_id() == self.tcx.lang_items().future_trait()
+            }
+            _ => false,
+        };
 
         let ret_ty = if let hir::FnRetTy::Return(ret_ty) = sig.decl.output {
             ret_ty
@@ -968,12 +3051,12 @@ fn suggest_impl_trait(
 
         let typeck_results = self.in_progress_typeck_results.map(|t| t.borrow()).unwrap();
 
-        let mut ret_types = visitor
+        let ret_types = visitor
             .returns
             .iter()
             .filter_map(|expr| typeck_results.node_type_opt(expr.hir_id))
             .map(|ty| self.resolve_vars_if_possible(ty));
-        let (last_ty, all_returns_have_same_type, only_never_return) = ret_types.clone().fold(
+        let (last_ty, all_returns_have_same_type, only_never_return) = ret_types.fold(
             (None, true, true),
             |(last_ty, mut same, only_never_return): (std::option::Option<Ty<'_>>, bool, bool),
              ty| {
@@ -997,28 +3080,93 @@ fn suggest_impl_trait(
                 (Some(ty), same, only_never_return && matches!(ty.kind(), ty::Never))
             },
         );
+        // The first returned value that doesn't satisfy one of the trait object's predicates, and
+        // that predicate. Functions can return values of the same type in many places, so each
+        // returned type is only checked once.
+        let mut nonconforming_return = None;
         let all_returns_conform_to_trait =
             if let Some(ty_ret_ty) = typeck_results.node_type_opt(ret_ty.hir_id) {
                 match ty_ret_ty.kind() {
-                    ty::Dynamic(predicates, _) => {
+                    ty::Dynamic(predicates, _) if !only_never_return => {
                         let cause = ObligationCause::misc(ret_ty.span, ret_ty.hir_id);
                         let param_env = ty::ParamEnv::empty();
-                        only_never_return
-                            || ret_types.all(|returned_ty| {
-                                predicates.iter().all(|predicate| {
-                                    let pred = predicate.with_self_ty(self.tcx, returned_ty);
-                                    let obl = Obligation::new(cause.clone(), param_env, pred);
-                                    self.predicate_may_hold(&obl)
-                                })
+                        let mut checked_tys = FxHashSet::default();
+                        nonconforming_return = visitor.returns.iter().find_map(|expr| {
+                            let returned_ty = typeck_results.node_type_opt(expr.hir_id)?;
+                            let returned_ty = self.resolve_vars_if_possible(returned_ty);
+                            if !checked_tys.insert(returned_ty) {
+                                return None;
+                            }
+                            predicates.iter().find_map(|predicate| {
+                                let pred = predicate.with_self_ty(self.tcx, returned_ty);
+                                let obl = Obligation::new(cause.clone(), param_env, pred);
+                                (!self.predicate_may_hold(&obl)).then(|| (expr.span, pred))
                             })
+                        });
+                        nonconforming_return.is_none()
                     }
+                    ty::Dynamic(..) => true,
                     _ => false,
                 }
             } else {
                 true
             };
-
         let sm = self.tcx.sess.source_map();
+
+        // Returning `Box::new(value)` from a `-> dyn Trait` function is likely a mix-up with
+        // `-> Box<dyn Trait>`. If all the boxed values are of the same type, they could be
+        // returned directly from a `-> impl Trait` function instead.
+        let unboxed_returns = match typeck_results.node_type_opt(ret_ty.hir_id).map(|t| t.kind()) {
+            Some(ty::Dynamic(predicates, _)) if !all_returns_conform_to_trait => {
+                let unbox = |expr: &hir::Expr<'_>| {
+                    let (callee, arg) = match expr.kind {
+                        hir::ExprKind::Call(callee, [arg]) => (callee, arg),
+                        _ => return None,
+                    };
+                    // Other constructors returning a `Box`, like `Box::from`, can't be undone by
+                    // returning their argument as is.
+                    let is_box_new = match &callee.kind {
+                        hir::ExprKind::Path(qpath) => {
+                            match typeck_results.qpath_res(qpath, callee.hir_id) {
+                                Res::Def(DefKind::AssocFn, def_id) => {
+                                    self.tcx.item_name(def_id) == sym::new
+                                        && self.tcx.impl_of_method(def_id).map_or(false, |impl_| {
+                                            self.tcx.type_of(impl_).ty_adt_def().map(|def| def.did)
+                                                == self.tcx.lang_items().owned_box()
+                                        })
+                                }
+                                _ => false,
+                            }
+                        }
+                        _ => false,
+                    };
+                    let ty = self.resolve_vars_if_possible(typeck_results.expr_ty_opt(expr)?);
+                    if !is_box_new || !ty.is_box() {
+                        return None;
+                    }
+                    Some((ty.boxed_ty(), expr.span, sm.span_to_snippet(arg.span).ok()?))
+                };
+                let unboxed =
+                    visitor.returns.iter().map(|&expr| unbox(expr)).collect::<Option<Vec<_>>>();
+                let cause = ObligationCause::misc(ret_ty.span, ret_ty.hir_id);
+                let conforms = |ty: Ty<'tcx>| {
+                    !ty.has_infer_types_or_consts()
+                        && predicates.iter().all(|predicate| {
+                            let pred = predicate.with_self_ty(self.tcx, ty);
+                            let obl = Obligation::new(cause.clone(), ty::ParamEnv::empty(), pred);
+                            self.predicate_may_hold(&obl)
+                        })
+                };
+                unboxed.filter(|unboxed| match unboxed.first() {
+                    Some(&(first_ty, ..)) => {
+                        unboxed.iter().all(|&(ty, ..)| ty == first_ty) && conforms(first_ty)
+                    }
+                    None => false,
+                })
+            }
+            _ => None,
+        };
+
         let snippet = if let (true, hir::TyKind::TraitObject(..), Ok(snippet), true) = (
             // Verify that we're dealing with a return `dyn Trait`
             ret_ty.span.overlaps(span),
@@ -1026,10 +3174,20 @@ fn suggest_impl_trait(
             sm.span_to_snippet(ret_ty.span),
             // If any of the return types does not conform to the trait, then we can't
             // suggest `impl Trait` nor trait objects: it is a type mismatch error.
-            all_returns_conform_to_trait,
+            all_returns_conform_to_trait || unboxed_returns.is_some(),
         ) {
             snippet
         } else {
+            if let (true, Some((return_span, pred))) =
+                (ret_ty.span.overlaps(span), nonconforming_return)
+            {
+                // Returning this value is a type error, which isn't reported separately because
+                // the return type is unsized, so make sure that it isn't missed.
+                err.span_label(
+                    return_span,
+                    format!("this returned value doesn't satisfy `{}`", pred),
+                );
+            }
             return false;
         };
         err.code(error_code!(E0746));
@@ -1041,9 +3199,31 @@ fn suggest_impl_trait(
         let trait_obj_msg = "for information on trait objects, see \
             <https://doc.rust-lang.org/book/ch17-02-trait-objects.html\
             #using-trait-objects-that-allow-for-values-of-different-types>";
+        // The notes below only explain the suggestions, so leave them out when asked to be terse.
+        let verbose = !self.terse_suggestions();
         let has_dyn = snippet.split_whitespace().next().map_or(false, |s| s == "dyn");
         let trait_obj = if has_dyn { &snippet[4..] } else { &snippet[..] };
-        if only_never_return {
+        if let Some(unboxed_returns) = unboxed_returns {
+            // Suggest `-> impl Trait` and removing the `Box::new` around every returned value.
+            let unboxed_ty = unboxed_returns[0].0;
+            let mut suggestions = unboxed_returns
+                .into_iter()
+                .map(|(_, span, snippet)| (span, snippet))
+                .collect::<Vec<_>>();
+            suggestions.push((ret_ty.span, format!("impl {}", trait_obj)));
+            err.multipart_suggestion(
+                &format!(
+                    "use `impl {1}` as the return type and return the `{}` values without \
+                     boxing them, as they all have that type, which implements `{1}`",
+                    unboxed_ty, trait_obj,
+                ),
+                suggestions,
+                Applicability::MachineApplicable,
+            );
+            if verbose {
+                err.note(impl_trait_msg);
+            }
+        } else if only_never_return {
             // No return paths, probably using `panic!()` or similar.
             // Suggest `-> T`, `-> impl Trait`, and if `Trait` is object safe, `-> Box<dyn Trait>`.
             suggest_trait_object_return_type_alternatives(
@@ -1064,7 +3244,35 @@ fn suggest_impl_trait(
                 format!("impl {}", trait_obj),
                 Applicability::MachineApplicable,
             );
-            err.note(impl_trait_msg);
+            if verbose {
+                err.note(impl_trait_msg);
+            }
+        } else if is_future {
+            // Every `async` block and `async fn` call has a future type of its own, so returning
+            // futures from several places means boxing them, and a boxed future has to be pinned
+            // to be awaited.
+            if let Some(mut suggestions) = visitor
+                .returns
+                .iter()
+                .map(|expr| {
+                    let snip = sm.span_to_snippet(expr.span).ok()?;
+                    Some((expr.span, format!("Box::pin({})", snip)))
+                })
+                .collect::<Option<Vec<_>>>()
+            {
+                suggestions.push((ret_ty.span, format!("std::pin::Pin<Box<dyn {}>>", trait_obj)));
+                err.multipart_suggestion(
+                    "return a pinned and boxed future instead",
+                    suggestions,
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            if verbose {
+                err.note(
+                    "if the futures are `Send`, `FutureExt::boxed` from the `futures` crate can be \
+                     used instead of `Box::pin`",
+                );
+            }
         } else {
             if is_object_safe {
                 // Suggest `-> Box<dyn Trait>` and `Box::new(returned_value)`.
@@ -1094,6 +3302,9 @@ fn suggest_impl_trait(
                     trait_obj,
                 ));
             }
+            if !verbose {
+                return true;
+            }
             err.note(trait_obj_msg);
             err.note(&format!(
                 "if all the returned values were of the same type you could use `impl {}` as the \
@@ -1101,7 +3312,50 @@ fn suggest_impl_trait(
                 trait_obj,
             ));
             err.note(impl_trait_msg);
-            err.note("you can create a new `enum` with a variant for each returned type");
+            // Collect the distinct returned types, in the order they appear, so that we can
+            // sketch out the `enum` the user would need to write.
+            let mut variant_tys: Vec<Ty<'tcx>> = vec![];
+            for expr in visitor.returns.iter() {
+                let ty = match typeck_results.node_type_opt(expr.hir_id) {
+                    Some(ty) => self.resolve_vars_if_possible(ty),
+                    None => continue,
+                };
+                // Mirror the fallback that inference would apply to unconstrained literals.
+                let ty = match ty.kind() {
+                    Infer(InferTy::IntVar(_)) => self.tcx.types.i32,
+                    Infer(InferTy::FloatVar(_)) => self.tcx.types.f64,
+                    ty::Never => continue,
+                    _ => ty,
+                };
+                if !variant_tys.contains(&ty) {
+                    variant_tys.push(ty);
+                }
+            }
+            if variant_tys.len() > 1
+                && variant_tys.iter().all(|ty| ty.is_suggestable() && !ty.references_error())
+            {
+                let variants = variant_tys
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| format!("\n    V{}({}),", i, ty))
+                    .collect::<String>();
+                err.help(&format!(
+                    "you can create a new `enum` with a variant for each returned type and \
+                     implement `{}` for it:\nenum Ret {{{}\n}}",
+                    trait_obj, variants,
+                ));
+                let wrappers = variant_tys
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| format!("`Ret::V{}(..)` for `{}`", i, ty))
+                    .collect::<Vec<_>>();
+                err.note(&format!(
+                    "then wrap each returned value in the variant for its type: {}",
+                    wrappers.join(", "),
+                ));
+            } else {
+                err.note("you can create a new `enum` with a variant for each returned type");
+            }
         }
         true
     }
@@ -1128,6 +3382,10 @@ fn point_at_returns_when_relevant(
             visitor.visit_body(&body);
             let typeck_results = self.in_progress_typeck_results.map(|t| t.borrow()).unwrap();
             for expr in &visitor.returns {
+                // `suggest_impl_trait` already explained what is wrong with this value.
+                if err.span.span_labels().iter().any(|label| label.span == expr.span) {
+                    continue;
+                }
                 if let Some(returned_ty) = typeck_results.node_type_opt(expr.hir_id) {
                     let ty = self.resolve_vars_if_possible(returned_ty);
                     err.span_label(expr.span, &format!("this returned value is of type `{}`", ty));
@@ -1143,32 +3401,44 @@ fn report_closure_arg_mismatch(
         expected_ref: ty::PolyTraitRef<'tcx>,
         found: ty::PolyTraitRef<'tcx>,
     ) -> DiagnosticBuilder<'tcx> {
+        fn build_sig_string<'tcx>(tcx: TyCtxt<'tcx>, inputs: ty::Binder<Vec<Ty<'tcx>>>) -> String {
+            inputs
+                .map_bound(|inputs| {
+                    tcx.mk_fn_sig(
+                        inputs.into_iter(),
+                        tcx.mk_ty_infer(ty::TyVar(ty::TyVid { index: 0 })),
+                        false,
+                        hir::Unsafety::Normal,
+                        abi::Abi::Rust,
+                    )
+                })
+                .to_string()
+        }
+
         crate fn build_fn_sig_string<'tcx>(
             tcx: TyCtxt<'tcx>,
             trait_ref: ty::PolyTraitRef<'tcx>,
         ) -> String {
             let inputs = trait_ref.skip_binder().substs.type_at(1);
-            let sig = if let ty::Tuple(inputs) = inputs.kind() {
-                tcx.mk_fn_sig(
-                    inputs.iter().map(|k| k.expect_ty()),
-                    tcx.mk_ty_infer(ty::TyVar(ty::TyVid { index: 0 })),
-                    false,
-                    hir::Unsafety::Normal,
-                    abi::Abi::Rust,
-                )
-            } else {
-                tcx.mk_fn_sig(
-                    std::iter::once(inputs),
-                    tcx.mk_ty_infer(ty::TyVar(ty::TyVid { index: 0 })),
-                    false,
-                    hir::Unsafety::Normal,
-                    abi::Abi::Rust,
-                )
+            let inputs = match inputs.kind() {
+                ty::Tuple(inputs) => inputs.iter().map(|k| k.expect_ty()).collect(),
+                _ => vec![inputs],
             };
-            trait_ref.rebind(sig).to_string()
+            build_sig_string(tcx, trait_ref.rebind(inputs))
         }
 
-        let argument_is_closure = expected_ref.skip_binder().substs.type_at(0).is_closure();
+        // Function pointers and items know their own signature, so use it instead of relying on
+        // the arguments of the trait reference they were selected with being a plain tuple.
+        let self_ty = expected_ref.skip_binder().self_ty();
+        let own_sig_string = match self_ty.kind() {
+            ty::FnPtr(_) | ty::FnDef(..) => {
+                let sig = self_ty.fn_sig(self.tcx);
+                build_sig_string(self.tcx, sig.map_bound(|sig| sig.inputs().to_vec()))
+            }
+            _ => build_fn_sig_string(self.tcx, expected_ref),
+        };
+
+        let argument_is_closure = self_ty.is_closure();
         let mut err = struct_span_err!(
             self.tcx.sess,
             span,
@@ -1181,35 +3451,460 @@ fn report_closure_arg_mismatch(
         err.span_label(span, found_str);
 
         let found_span = found_span.unwrap_or(span);
-        let expected_str =
-            format!("found signature of `{}`", build_fn_sig_string(self.tcx, expected_ref));
+        let expected_str = format!("found signature of `{}`", own_sig_string);
         err.span_label(found_span, expected_str);
 
+        self.note_conflicting_crate_versions(&mut err, found, expected_ref);
+
+        if argument_is_closure {
+            self.note_higher_ranked_closure_mismatch(&mut err, expected_ref, found);
+            self.suggest_closure_param_annotations(&mut err, expected_ref, found);
+        }
+
         err
     }
 
+    /// The signatures in a closure argument mismatch print the same when their argument types
+    /// have the same path but come from different crates, which usually means that two versions
+    /// of the same crate ended up in the dependency graph. Point that out.
+    fn note_conflicting_crate_versions(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+        found_ref: ty::PolyTraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let def_id = |ty: Ty<'tcx>| match *ty.kind() {
+            ty::Adt(def, _) => Some(def.did),
+            ty::Foreign(def_id) => Some(def_id),
+            ty::Dynamic(predicates, _) => predicates.principal_def_id(),
+            _ => None,
+        };
+        let expected_tys = expected_ref.skip_binder().substs.type_at(1).walk();
+        let found_tys = found_ref.skip_binder().substs.type_at(1).walk();
+        let mut noted_crates = FxHashSet::default();
+        for (expected, found) in expected_tys.zip(found_tys) {
+            let (expected, found) = match (expected.unpack(), found.unpack()) {
+                (GenericArgKind::Type(expected), GenericArgKind::Type(found)) => (expected, found),
+                _ => continue,
+            };
+            let (expected_did, found_did) = match (def_id(expected), def_id(found)) {
+                (Some(expected_did), Some(found_did)) => (expected_did, found_did),
+                _ => continue,
+            };
+            // Only external crates, a local module with the same path would be a false positive.
+            if expected_did.is_local()
+                || found_did.is_local()
+                || expected_did.krate == found_did.krate
+            {
+                continue;
+            }
+            if tcx.def_path_str(expected_did) == tcx.def_path_str(found_did)
+                && noted_crates.insert(expected_did.krate)
+            {
+                err.note(&format!(
+                    "perhaps two different versions of crate `{}` are being used?",
+                    tcx.crate_name(expected_did.krate),
+                ));
+            }
+        }
+    }
+
+    /// When the closure's signature only differs from the expected higher-ranked one in its
+    /// lifetimes, the printed signatures look alike: explain that the closure was inferred with
+    /// specific lifetimes where it has to accept any.
+    fn note_higher_ranked_closure_mismatch(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        closure_ref: ty::PolyTraitRef<'tcx>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let inputs = |trait_ref: ty::PolyTraitRef<'tcx>| {
+            trait_ref.map_bound(|trait_ref| trait_ref.substs.type_at(1))
+        };
+        let (closure_inputs, expected_inputs) = (inputs(closure_ref), inputs(expected_ref));
+        let erase =
+            |inputs: ty::Binder<Ty<'tcx>>| tcx.erase_regions(tcx.erase_late_bound_regions(inputs));
+        if erase(closure_inputs) != erase(expected_inputs)
+            || !expected_inputs.skip_binder().has_escaping_bound_vars()
+            || !matches!(expected_inputs.skip_binder().kind(), ty::Tuple(_))
+        {
+            return;
+        }
+
+        // Probe whether a callable with the closure's signature, lifetimes included, satisfies
+        // the expected bound. If it doesn't, the lifetimes are what the closure is missing: its
+        // parameter types match the expected ones once the lifetimes are erased.
+        let fn_ptr = tcx.mk_fn_ptr(closure_inputs.map_bound(|inputs| {
+            tcx.mk_fn_sig(
+                inputs.tuple_fields(),
+                tcx.mk_unit(),
+                false,
+                hir::Unsafety::Normal,
+                abi::Abi::Rust,
+            )
+        }));
+        let obligation = Obligation::new(
+            ObligationCause::dummy(),
+            ty::ParamEnv::empty(),
+            expected_ref
+                .map_bound(|trait_ref| {
+                    ty::TraitRef::new(
+                        trait_ref.def_id,
+                        tcx.mk_substs_trait(fn_ptr, &trait_ref.substs[1..]),
+                    )
+                })
+                .without_const()
+                .to_predicate(tcx),
+        );
+        if !self.predicate_may_hold(&obligation) {
+            err.note(
+                "the closure's parameter types were inferred with specific lifetimes, but the \
+                 closure must accept arguments with any lifetime",
+            );
+        }
+    }
+
+    /// When the closure in `closure_ref` doesn't annotate some of its parameters and the types
+    /// it inferred for them don't match `expected_ref`, suggest annotating them with the
+    /// expected types, e.g. `|x| ...` → `|x: &u32| ...`.
+    fn suggest_closure_param_annotations(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        closure_ref: ty::PolyTraitRef<'tcx>,
+        expected_ref: ty::PolyTraitRef<'tcx>,
+    ) {
+        let closure_def_id = match *closure_ref.skip_binder().self_ty().kind() {
+            ty::Closure(def_id, _) => def_id,
+            _ => return,
+        };
+        let hir = self.tcx.hir();
+        let (decl, body_id) = match hir.get_if_local(closure_def_id) {
+            Some(Node::Expr(hir::Expr {
+                kind: hir::ExprKind::Closure(_, decl, body_id, ..),
+                ..
+            })) => (decl, *body_id),
+            _ => return,
+        };
+        // The expected signature can be higher-ranked, but the annotations are written on the
+        // closure itself, where the lifetimes can be left elided.
+        let expected_inputs = self.tcx.erase_late_bound_regions(
+            expected_ref.map_bound(|trait_ref| trait_ref.substs.type_at(1)),
+        );
+        let expected_inputs = match expected_inputs.kind() {
+            ty::Tuple(tys) => tys.iter().map(|ty| ty.expect_ty()).collect::<Vec<_>>(),
+            _ => vec![expected_inputs],
+        };
+        let params = hir.body(body_id).params;
+        if expected_inputs.len() != params.len() {
+            return;
+        }
+        // Only suggest types the user can actually write down.
+        if expected_inputs.iter().any(|ty| {
+            ty.has_infer_types_or_consts() || ty.has_projections() || ty.references_error()
+        }) {
+            return;
+        }
+        let sugg = decl
+            .inputs
+            .iter()
+            .zip(params)
+            .zip(expected_inputs)
+            .filter(|((input, _), _)| matches!(input.kind, hir::TyKind::Infer))
+            .map(|((_, param), ty)| (param.pat.span.shrink_to_hi(), format!(": {}", ty)))
+            .collect::<Vec<_>>();
+        if sugg.is_empty() {
+            return;
+        }
+        err.multipart_suggestion(
+            &format!(
+                "consider specifying the type{s} of the closure parameter{s}",
+                s = pluralize!(sugg.len())
+            ),
+            sugg,
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn suggest_fully_qualified_path(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         def_id: DefId,
         span: Span,
         trait_ref: DefId,
+        body_id: hir::HirId,
     ) {
-        if let Some(assoc_item) = self.tcx.opt_associated_item(def_id) {
+        let tcx = self.tcx;
+        if let Some(assoc_item) = tcx.opt_associated_item(def_id) {
             if let ty::AssocKind::Const | ty::AssocKind::Type = assoc_item.kind {
                 err.note(&format!(
                     "{}s cannot be accessed directly on a `trait`, they can only be \
                         accessed through a specific `impl`",
                     assoc_item.kind.as_def_kind().descr(def_id)
                 ));
-                err.span_suggestion(
-                    span,
-                    "use the fully qualified path to an implementation",
-                    format!("<Type as {}>::{}", self.tcx.def_path_str(trait_ref), assoc_item.ident),
-                    Applicability::HasPlaceholders,
-                );
+
+                // Name an implementing type if there is one that can be named from here, a
+                // suggestion through a type that is private to another module wouldn't compile.
+                // Every type that the implementing type is made of has to be accessible, not just
+                // the outermost one, as in `Box<Private>`.
+                let module = tcx.parent_module(body_id).to_def_id();
+                let is_accessible = |def_id| tcx.visibility(def_id).is_accessible_from(module, tcx);
+                let (accessible, inaccessible): (Vec<_>, Vec<_>) = tcx
+                    .all_impls(trait_ref)
+                    .map(|impl_def_id| tcx.type_of(impl_def_id))
+                    .filter(|self_ty| !self_ty.needs_subst())
+                    .partition(|self_ty| {
+                        self_ty.walk().all(|arg| match arg.unpack() {
+                            GenericArgKind::Type(ty) => match *ty.kind() {
+                                ty::Adt(def, _) => is_accessible(def.did),
+                                ty::Foreign(def_id) => is_accessible(def_id),
+                                _ => true,
+                            },
+                            _ => true,
+                        })
+                    });
+                let trait_path = tcx.def_path_str(trait_ref);
+                if let Some(self_ty) = accessible.first() {
+                    // The type isn't necessarily in scope, so don't trim its path.
+                    let self_ty = with_no_trimmed_paths(|| self_ty.to_string());
+                    err.span_suggestion(
+                        span,
+                        "use the fully qualified path to an implementation",
+                        format!("<{} as {}>::{}", self_ty, trait_path, assoc_item.ident),
+                        Applicability::MaybeIncorrect,
+                    );
+                    return;
+                }
+                if let Some(self_ty) = inaccessible.first() {
+                    err.note(&format!(
+                        "`{}` implements `{}`, but it isn't accessible from here",
+                        self_ty, trait_path,
+                    ));
+                }
+                err.span_suggestion(
+                    span,
+                    "use the fully qualified path to an implementation",
+                    format!("<Type as {}>::{}", trait_path, assoc_item.ident),
+                    Applicability::HasPlaceholders,
+                );
+            }
+        }
+    }
+
+    /// When a bound is ambiguous because the type of an unannotated `let` binding could not be
+    /// inferred, suggest giving that binding an explicit type: `let x = ...;` → `let x: T = ...;`.
+    /// This is for when the binding only holds part of the ambiguous type, such as one element of
+    /// a tuple, as the error otherwise already points at it.
+    fn suggest_add_type_annotation(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+        body_id: Option<hir::BodyId>,
+    ) {
+        let (body_id, typeck_results) = match (body_id, self.in_progress_typeck_results) {
+            (Some(body_id), Some(typeck_results)) => (body_id, typeck_results.borrow()),
+            _ => return,
+        };
+        let self_ty = self.resolve_vars_if_possible(trait_ref.skip_binder().self_ty());
+        let ty_vids = |ty: Ty<'tcx>| {
+            ty.walk()
+                .filter_map(|arg| match arg.unpack() {
+                    GenericArgKind::Type(ty) => match *ty.kind() {
+                        ty::Infer(ty::TyVar(vid)) => Some(self.root_var(vid)),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let target_vids = ty_vids(self_ty);
+        if target_vids.is_empty() {
+            // The ambiguity doesn't come from an uninferred type, there's nothing to annotate.
+            return;
+        }
+
+        let mut visitor = UnannotatedLocalsVisitor::default();
+        visitor.visit_body(self.tcx.hir().body(body_id));
+        for local in visitor.locals {
+            if local.pat.span.from_expansion() || local.pat.span.desugaring_kind().is_some() {
+                continue;
+            }
+            let ident = match local.pat.simple_ident() {
+                Some(ident) => ident,
+                None => continue,
+            };
+            let local_ty = match typeck_results.node_type_opt(local.hir_id) {
+                Some(ty) => self.resolve_vars_if_possible(ty),
+                None => continue,
+            };
+            if !ty_vids(local_ty).iter().any(|vid| target_vids.contains(vid)) {
+                continue;
+            }
+            // The inference failure error already points at the binding when its type contains
+            // the one that couldn't be inferred, and says how to annotate it.
+            if err
+                .span
+                .span_labels()
+                .iter()
+                .any(|span_label| span_label.label.is_some() && span_label.span == local.pat.span)
+            {
+                return;
+            }
+            // If we only know the type partially, like `Vec<_>`, we can still use it as a
+            // starting point for the annotation.
+            let (annotation, applicability) = if local_ty.is_ty_var() {
+                ("Type".to_string(), Applicability::HasPlaceholders)
+            } else if local_ty.has_infer_types_or_consts() {
+                (local_ty.to_string(), Applicability::HasPlaceholders)
+            } else {
+                (local_ty.to_string(), Applicability::MaybeIncorrect)
+            };
+            err.span_suggestion_verbose(
+                local.pat.span.shrink_to_hi(),
+                &format!("consider giving `{}` an explicit type", ident),
+                format!(": {}", annotation),
+                applicability,
+            );
+            return;
+        }
+    }
+
+    /// A bare `.collect()` leaves the type of the collection to be inferred from how it's used.
+    /// When nothing constrains it, suggest a turbofish with a collection that the items can be
+    /// gathered into, and list the other common ones that would work as well.
+    fn suggest_collect_target_type(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        target_ty: Ty<'tcx>,
+        body_id: Option<hir::BodyId>,
+    ) {
+        let tcx = self.tcx;
+        let target_vid = match *self.resolve_vars_if_possible(target_ty).kind() {
+            ty::Infer(ty::TyVar(vid)) => self.root_var(vid),
+            _ => return,
+        };
+        let (iterator, from_iterator) = match (
+            tcx.get_diagnostic_item(sym::Iterator),
+            tcx.get_diagnostic_item(sym::FromIterator),
+        ) {
+            (Some(iterator), Some(from_iterator)) => (iterator, from_iterator),
+            _ => return,
+        };
+        let (body_id, typeck_results) = match (body_id, self.in_progress_typeck_results) {
+            (Some(body_id), Some(typeck_results)) => (body_id, typeck_results.borrow()),
+            _ => return,
+        };
+
+        // Find the `Iterator::collect` call, without a turbofish, that produces the type.
+        let mut visitor = NamedMethodCallsVisitor { name: sym::collect, calls: vec![] };
+        visitor.visit_body(tcx.hir().body(body_id));
+        let is_target = |ty: Ty<'tcx>| match *self.resolve_vars_if_possible(ty).kind() {
+            ty::Infer(ty::TyVar(vid)) => self.root_var(vid) == target_vid,
+            _ => false,
+        };
+        let (segment, receiver) = match visitor.calls.into_iter().find(|(segment, expr, _)| {
+            segment.args.is_none()
+                && typeck_results
+                    .type_dependent_def_id(expr.hir_id)
+                    .map_or(false, |def_id| tcx.parent(def_id) == Some(iterator))
+                && typeck_results.node_type_opt(expr.hir_id).map_or(false, |ty| is_target(ty))
+        }) {
+            Some((segment, _, receiver)) => (segment, receiver),
+            None => return,
+        };
+        let iter_ty = match typeck_results.expr_ty_adjusted_opt(receiver) {
+            Some(ty) => self.resolve_vars_if_possible(ty),
+            None => return,
+        };
+        if iter_ty.has_infer_types_or_consts() {
+            return;
+        }
+
+        // `<I as Iterator>::Item`
+        let item_def_id =
+            match tcx.associated_items(iterator).filter_by_name_unhygienic(sym::Item).next() {
+                Some(item) => item.def_id,
+                None => return,
+            };
+        let mut selcx = SelectionContext::new(self);
+        let mut obligations = vec![];
+        let item_ty = normalize_projection_type(
+            &mut selcx,
+            obligation.param_env,
+            ty::ProjectionTy { substs: tcx.mk_substs_trait(iter_ty, &[]), item_def_id },
+            obligation.cause.clone(),
+            0,
+            &mut obligations,
+        );
+        let item_ty = self.resolve_vars_if_possible(item_ty);
+        if item_ty.has_infer_types_or_consts() {
+            return;
+        }
+
+        let span = obligation.cause.span;
+        let targets = [
+            sym::vec_type,
+            sym::vecdeque_type,
+            sym::hashset_type,
+            sym::hashmap_type,
+            sym::string_type,
+        ]
+        .iter()
+        .filter_map(|&name| tcx.get_diagnostic_item(name))
+        .map(|def_id| {
+            // Leave the element types to be inferred, and parameters like the hasher of a
+            // `HashSet` at their defaults.
+            let substs = InternalSubsts::for_item(tcx, def_id, |param, substs| match param.kind {
+                ty::GenericParamDefKind::Type { has_default: true, .. } => {
+                    tcx.type_of(param.def_id).subst(tcx, substs).into()
+                }
+                _ => self.var_for_def(span, param),
+            });
+            tcx.mk_adt(tcx.adt_def(def_id), substs)
+        })
+        .filter(|&target| {
+            let trait_ref =
+                ty::TraitRef::new(from_iterator, tcx.mk_substs_trait(target, &[item_ty.into()]));
+            let obligation = Obligation::new(
+                obligation.cause.clone(),
+                obligation.param_env,
+                trait_ref.without_const().to_predicate(tcx),
+            );
+            self.predicate_may_hold(&obligation)
+        })
+        .map(|target| format!("`{}`", target))
+        .collect::<Vec<_>>();
+        let first = match targets.first() {
+            Some(first) => first.trim_matches('`').to_string(),
+            None => return,
+        };
+
+        if let [init @ .., last] = &targets[..] {
+            if !init.is_empty() {
+                err.help(&format!(
+                    "`collect` can gather items of type `{}` into {} or {}",
+                    item_ty,
+                    init.join(", "),
+                    last,
+                ));
             }
         }
+        // Naming a collection is more helpful than the generic `::<B>` placeholder the inference
+        // error may have suggested in the same place.
+        let turbofish_span = segment.ident.span.shrink_to_hi();
+        err.suggestions.retain(|suggestion| {
+            !suggestion.substitutions.iter().any(|substitution| {
+                substitution.parts.iter().any(|part| part.span == turbofish_span)
+            })
+        });
+        err.span_suggestion_verbose(
+            turbofish_span,
+            "consider specifying the type of the collection",
+            format!("::<{}>", first),
+            Applicability::MaybeIncorrect,
+        );
     }
 
     /// Adds an async-await specific note to the diagnostic when the future does not implement
@@ -1264,7 +3959,6 @@ fn maybe_note_obligation_cause_for_async_await(
                 obligation.cause.span={:?}",
             obligation.predicate, obligation.cause.span
         );
-        let hir = self.tcx.hir();
 
         // Attempt to detect an async-await error by looking at the obligation causes, looking
         // for a generator to be present.
@@ -1288,15 +3982,20 @@ fn maybe_note_obligation_cause_for_async_await(
         // - `BuiltinDerivedObligation` with `impl std::future::Future` (A)
         // - `BindingObligation` with `impl_send (Send requirement)
         //
-        // The first obligation in the chain is the most useful and has the generator that captured
-        // the type. The last generator (`outer_generator` below) has information about where the
-        // bound was introduced. At least one generator should be present for this diagnostic to be
+        // The first generator in the chain is usually the most useful, as it's the one that
+        // captured the type, but it can't always be explained: it may come from another crate, or
+        // only hold a type parameter that happens to be instantiated with the offending type. In
+        // that case the next generator out holds the first one's future, so try each in turn. The
+        // last generator (`outer_generator` below) has information about where the bound was
+        // introduced. At least one generator should be present for this diagnostic to be
         // modified.
         let (mut trait_ref, mut target_ty) = match obligation.predicate.skip_binders() {
             ty::PredicateAtom::Trait(p, _) => (Some(p.trait_ref), Some(p.self_ty())),
             _ => (None, None),
         };
-        let mut generator = None;
+        // The generators in the chain, innermost first, each with the trait ref and type that its
+        // interior or upvars are required to satisfy.
+        let mut generators = vec![];
         let mut outer_generator = None;
         let mut next_code = Some(&obligation.cause.code);
 
@@ -1318,8 +4017,13 @@ fn maybe_note_obligation_cause_for_async_await(
 
                     match *ty.kind() {
                         ty::Generator(did, ..) => {
-                            generator = generator.or(Some(did));
+                            if let (Some(trait_ref), Some(target_ty)) =
+                                (trait_ref.take(), target_ty.take())
+                            {
+                                generators.push((did, trait_ref, target_ty));
+                            }
                             outer_generator = Some(did);
+                            seen_upvar_tys_infer_tuple = false;
                         }
                         ty::GeneratorWitness(..) => {}
                         ty::Tuple(_) if !seen_upvar_tys_infer_tuple => {
@@ -1329,11 +4033,10 @@ fn maybe_note_obligation_cause_for_async_await(
 
                             seen_upvar_tys_infer_tuple = true;
                         }
-                        _ if generator.is_none() => {
+                        _ => {
                             trait_ref = Some(derived_obligation.parent_trait_ref.skip_binder());
                             target_ty = Some(ty);
                         }
-                        _ => {}
                     }
 
                     next_code = Some(derived_obligation.parent_code.as_ref());
@@ -1343,18 +4046,37 @@ fn maybe_note_obligation_cause_for_async_await(
         }
 
         // Only continue if a generator was found.
-        debug!(
-            "maybe_note_obligation_cause_for_async_await: generator={:?} trait_ref={:?} \
-                target_ty={:?}",
-            generator, trait_ref, target_ty
-        );
-        let (generator_did, trait_ref, target_ty) = match (generator, trait_ref, target_ty) {
-            (Some(generator_did), Some(trait_ref), Some(target_ty)) => {
-                (generator_did, trait_ref, target_ty)
+        debug!("maybe_note_obligation_cause_for_async_await: generators={:?}", generators);
+        for (i, &(generator_did, trait_ref, target_ty)) in generators.iter().enumerate() {
+            if self.note_generator_holding_ty(
+                err,
+                obligation,
+                generator_did,
+                trait_ref,
+                target_ty,
+                outer_generator,
+                next_code,
+            ) {
+                self.note_awaits_of_inner_futures(err, &generators[i..]);
+                return true;
             }
-            _ => return false,
-        };
+        }
+        false
+    }
 
+    /// Adds the note described in `maybe_note_obligation_cause_for_async_await`'s documentation
+    /// comment if the interior or the upvars of `generator_did` hold `target_ty`.
+    fn note_generator_holding_ty(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        generator_did: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        target_ty: Ty<'tcx>,
+        outer_generator: Option<DefId>,
+        next_code: Option<&ObligationCauseCode<'tcx>>,
+    ) -> bool {
+        let hir = self.tcx.hir();
         let span = self.tcx.def_span(generator_did);
 
         // Do not ICE on closure typeck (#66868).
@@ -1364,7 +4086,9 @@ fn maybe_note_obligation_cause_for_async_await(
 
         // Get the typeck results from the infcx if the generator is the function we are
         // currently type-checking; otherwise, get them by performing a query.
-        // This is needed to avoid cycles.
+        // This is needed to avoid cycles. The query's result is cached, with or without the
+        // parallel compiler, so several errors pointing into the same generator only type-check
+        // it once.
         let in_progress_typeck_results = self.in_progress_typeck_results.map(|t| t.borrow());
         let generator_did_root = self.tcx.closure_base_def_id(generator_did);
         debug!(
@@ -1389,16 +4113,11 @@ fn maybe_note_obligation_cause_for_async_await(
             .map(|def_id| hir.local_def_id_to_hir_id(def_id))
             .and_then(|hir_id| hir.maybe_body_owned_by(hir_id))
             .map(|body_id| hir.body(body_id));
-        let mut visitor = AwaitsVisitor::default();
-        if let Some(body) = generator_body {
-            visitor.visit_body(body);
-        }
-        debug!("maybe_note_obligation_cause_for_async_await: awaits = {:?}", visitor.awaits);
 
         // Look for a type inside the generator interior that matches the target type to get
         // a span.
         let target_ty_erased = self.tcx.erase_regions(target_ty);
-        let ty_matches = |ty| -> bool {
+        let erase = |ty: ty::Binder<Ty<'tcx>>| -> Ty<'tcx> {
             // Careful: the regions for types that appear in the
             // generator interior are not generally known, so we
             // want to erase them when comparing (and anyway,
@@ -1412,7 +4131,10 @@ fn maybe_note_obligation_cause_for_async_await(
             // `erase_regions` and so we must also call
             // `erase_late_bound_regions`.
             let ty_erased = self.tcx.erase_late_bound_regions(ty);
-            let ty_erased = self.tcx.erase_regions(ty_erased);
+            self.tcx.erase_regions(ty_erased)
+        };
+        let ty_matches = |ty: ty::Binder<Ty<'tcx>>| -> bool {
+            let ty_erased = erase(ty);
             let eq = ty::TyS::same_type(ty_erased, target_ty_erased);
             debug!(
                 "maybe_note_obligation_cause_for_async_await: ty_erased={:?} \
@@ -1421,48 +4143,81 @@ fn maybe_note_obligation_cause_for_async_await(
             );
             eq
         };
+        // The value held across the await might be a reference to the type that doesn't
+        // implement the trait, or the other way around: `&T` might be in the obligation while
+        // the generator holds a `T` that is only borrowed when it is used.
+        let ty_matches_through_ref = |ty: ty::Binder<Ty<'tcx>>| -> bool {
+            let ty_erased = erase(ty);
+            match (ty_erased.kind(), target_ty_erased.kind()) {
+                (&ty::Ref(_, inner_ty, _), _) => ty::TyS::same_type(inner_ty, target_ty_erased),
+                (_, &ty::Ref(_, inner_ty, _)) => ty::TyS::same_type(ty_erased, inner_ty),
+                _ => false,
+            }
+        };
 
         let mut interior_or_upvar_span = None;
         let mut interior_extra_info = None;
+        // The upvar and its type, if the offending type is a reference to an upvar that was
+        // captured by reference.
+        let mut upvar_captured_by_ref = None;
+        // The upvar, if the offending type is that of an upvar moved into the generator.
+        let mut moved_upvar = None;
+        // The expression that produced the value held in the generator interior, if known.
+        let mut held_expr = None;
 
         if let Some(upvars) = self.tcx.upvars_mentioned(generator_did) {
             interior_or_upvar_span = upvars.iter().find_map(|(upvar_id, upvar)| {
                 let upvar_ty = typeck_results.node_type(*upvar_id);
                 let upvar_ty = self.resolve_vars_if_possible(upvar_ty);
+                let capture = typeck_results
+                    .upvar_capture_map
+                    .get(&ty::UpvarId::new(*upvar_id, generator_did.expect_local()));
                 if ty_matches(ty::Binder::dummy(upvar_ty)) {
-                    Some(GeneratorInteriorOrUpvar::Upvar(upvar.span))
-                } else {
-                    None
+                    if let Some(ty::UpvarCapture::ByValue(capture_span)) = capture {
+                        // Point at the use that moved the value in, which needn't be the first.
+                        moved_upvar = Some(*upvar_id);
+                        let span = capture_span.unwrap_or(upvar.span);
+                        return Some(GeneratorInteriorOrUpvar::Upvar(span));
+                    }
+                    return Some(GeneratorInteriorOrUpvar::Upvar(upvar.span));
                 }
+                if let (Some(ty::UpvarCapture::ByRef(_)), &ty::Ref(_, inner_ty, _)) =
+                    (capture, target_ty_erased.kind())
+                {
+                    if ty::TyS::same_type(erase(ty::Binder::dummy(upvar_ty)), inner_ty) {
+                        upvar_captured_by_ref = Some((*upvar_id, upvar_ty));
+                        return Some(GeneratorInteriorOrUpvar::Upvar(upvar.span));
+                    }
+                }
+                None
             });
         };
 
         // The generator interior types share the same binders
-        if let Some(cause) =
-            typeck_results.generator_interior_types.as_ref().skip_binder().iter().find(
-                |ty::GeneratorInteriorTypeCause { ty, .. }| {
-                    ty_matches(typeck_results.generator_interior_types.rebind(ty))
-                },
-            )
+        let find_interior_cause = |matches: &dyn Fn(ty::Binder<Ty<'tcx>>) -> bool| {
+            typeck_results
+                .generator_interior_types
+                .as_ref()
+                .skip_binder()
+                .iter()
+                .find(|cause| matches(typeck_results.generator_interior_types.rebind(cause.ty)))
+        };
+        // Prefer an exact match, only falling back to looking through references if there's none.
+        if let Some(cause) = find_interior_cause(&ty_matches)
+            .or_else(|| find_interior_cause(&ty_matches_through_ref))
         {
             // Check to see if any awaited expressions have the target type.
-            let from_awaited_ty = visitor
-                .awaits
-                .into_iter()
-                .map(|id| hir.expect_expr(id))
-                .find(|await_expr| {
-                    let ty = typeck_results.expr_ty_adjusted(&await_expr);
-                    debug!(
-                        "maybe_note_obligation_cause_for_async_await: await_expr={:?}",
-                        await_expr
-                    );
+            let awaited_expr = generator_body.and_then(|body| {
+                find_awaited_expr(self.tcx, body, typeck_results, |ty| {
                     ty_matches(ty::Binder::dummy(ty))
                 })
-                .map(|expr| expr.span);
+            });
+            let from_awaited_ty = awaited_expr.map(|expr| expr.span);
             let ty::GeneratorInteriorTypeCause { span, scope_span, yield_span, expr, .. } = cause;
 
             interior_or_upvar_span = Some(GeneratorInteriorOrUpvar::Interior(*span));
             interior_extra_info = Some((*scope_span, *yield_span, *expr, from_awaited_ty));
+            held_expr = awaited_expr.map(|expr| expr.hir_id).or(*expr);
         };
 
         debug!(
@@ -1475,6 +4230,7 @@ fn maybe_note_obligation_cause_for_async_await(
                 err,
                 interior_or_upvar_span,
                 interior_extra_info,
+                moved_upvar,
                 generator_body,
                 outer_generator,
                 trait_ref,
@@ -1483,12 +4239,286 @@ fn maybe_note_obligation_cause_for_async_await(
                 obligation,
                 next_code,
             );
+            if let (GeneratorInteriorOrUpvar::Upvar(_), Some((upvar_id, upvar_ty))) =
+                (interior_or_upvar_span, upvar_captured_by_ref)
+            {
+                self.suggest_move_capture(
+                    err,
+                    obligation,
+                    generator_did,
+                    trait_ref,
+                    upvar_id,
+                    upvar_ty,
+                );
+            }
+            if let Some(held_expr) = held_expr {
+                self.suggest_auto_trait_on_boxed_future(
+                    err,
+                    obligation.param_env,
+                    trait_ref,
+                    target_ty,
+                    held_expr,
+                    typeck_results,
+                );
+            }
             true
         } else {
             false
         }
     }
 
+    /// When the generator that holds the offending type isn't the outermost one, point at where
+    /// each enclosing future awaits the one inside it, so that the chain from the future that
+    /// doesn't meet the bound to the value that causes it can be followed.
+    fn note_awaits_of_inner_futures(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        generators: &[(DefId, ty::TraitRef<'tcx>, Ty<'tcx>)],
+    ) {
+        let hir = self.tcx.hir();
+        let in_progress_typeck_results = self.in_progress_typeck_results.map(|t| t.borrow());
+        for pair in generators.windows(2) {
+            let (inner_did, outer_did, awaited_ty) = (pair[0].0, pair[1].0, pair[1].2);
+            let awaited = match self.tcx.generator_kind(inner_did) {
+                Some(GeneratorKind::Async(AsyncGeneratorKind::Fn)) => {
+                    match self
+                        .tcx
+                        .parent(inner_did)
+                        .and_then(|parent| self.tcx.opt_item_name(parent))
+                    {
+                        Some(name) => format!("the future returned by `{}`", name),
+                        None => continue,
+                    }
+                }
+                Some(GeneratorKind::Async(AsyncGeneratorKind::Block)) => {
+                    "the future created by this async block".to_string()
+                }
+                Some(GeneratorKind::Async(AsyncGeneratorKind::Closure)) => {
+                    "the future created by this async closure".to_string()
+                }
+                _ => continue,
+            };
+            let outer_def_id = match outer_did.as_local() {
+                Some(def_id) => def_id,
+                None => continue,
+            };
+            let body = match hir.maybe_body_owned_by(hir.local_def_id_to_hir_id(outer_def_id)) {
+                Some(body_id) => hir.body(body_id),
+                None => continue,
+            };
+            let query_typeck_results;
+            let typeck_results: &TypeckResults<'tcx> = match &in_progress_typeck_results {
+                Some(t) if t.hir_owner.to_def_id() == self.tcx.closure_base_def_id(outer_did) => t,
+                _ => {
+                    query_typeck_results = self.tcx.typeck(outer_def_id);
+                    &query_typeck_results
+                }
+            };
+            let awaited_ty = self.tcx.erase_regions(awaited_ty);
+            let awaited_expr = find_awaited_expr(self.tcx, body, typeck_results, |ty| {
+                ty::TyS::same_type(self.tcx.erase_regions(ty), awaited_ty)
+            });
+            if let Some(awaited_expr) = awaited_expr {
+                err.span_note(awaited_expr.span, &format!("{} is awaited here", awaited));
+            }
+        }
+    }
+
+    /// When a non-`move` generator captures `upvar_id` by reference and the reference doesn't
+    /// satisfy `trait_ref` while the captured value itself would, suggest capturing by value:
+    /// `async { x.get() }` → `async move { x.get() }`.
+    fn suggest_move_capture(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        obligation: &PredicateObligation<'tcx>,
+        generator_did: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        upvar_id: hir::HirId,
+        upvar_ty: Ty<'tcx>,
+    ) {
+        let hir = self.tcx.hir();
+        let generator_expr = match hir.get(hir.local_def_id_to_hir_id(generator_did.expect_local()))
+        {
+            Node::Expr(expr) => expr,
+            _ => return,
+        };
+        if !matches!(generator_expr.kind, hir::ExprKind::Closure(hir::CaptureBy::Ref, ..)) {
+            return;
+        }
+        let kind = match self.tcx.generator_kind(generator_did) {
+            Some(GeneratorKind::Async(AsyncGeneratorKind::Block)) => "async block",
+            Some(GeneratorKind::Async(AsyncGeneratorKind::Closure)) => "async closure",
+            Some(GeneratorKind::Gen) => "generator",
+            _ => return,
+        };
+
+        // Only suggest this if capturing the value itself would satisfy the bound.
+        let trait_ref = ty::TraitRef::new(
+            trait_ref.def_id,
+            self.tcx.mk_substs_trait(upvar_ty, &trait_ref.substs[1..]),
+        );
+        let by_value_obligation = Obligation::new(
+            obligation.cause.clone(),
+            obligation.param_env,
+            trait_ref.without_const().to_predicate(self.tcx),
+        );
+        if !self.predicate_must_hold_modulo_regions(&by_value_obligation) {
+            return;
+        }
+
+        let span = generator_expr.span;
+        let (span, suggestion) = match self.tcx.sess.source_map().span_to_snippet(span) {
+            Ok(snippet) if snippet.starts_with("async") => {
+                (span.with_hi(span.lo() + BytePos(5)), "async move".to_string())
+            }
+            Ok(_) => (span.shrink_to_lo(), "move ".to_string()),
+            Err(_) => return,
+        };
+        err.span_suggestion_verbose(
+            span,
+            &format!(
+                "consider using a `move` {} to capture `{}` by value instead of by reference",
+                kind,
+                hir.name(upvar_id),
+            ),
+            suggestion,
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// When the value held across an await is a boxed trait-object future that doesn't implement
+    /// `Send` or `Sync`, such as `Pin<Box<dyn Future<Output = ()>>>`, suggest adding the bound to
+    /// the trait object where its type is written, as long as every future that is boxed into it
+    /// implements the trait: `dyn Future<Output = ()>` → `dyn Future<Output = ()> + Send`.
+    fn suggest_auto_trait_on_boxed_future(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        param_env: ty::ParamEnv<'tcx>,
+        trait_ref: ty::TraitRef<'tcx>,
+        target_ty: Ty<'tcx>,
+        held_expr: hir::HirId,
+        typeck_results: &TypeckResults<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let hir = tcx.hir();
+        if !tcx.is_diagnostic_item(sym::send_trait, trait_ref.def_id)
+            && !tcx.is_diagnostic_item(sym::sync_trait, trait_ref.def_id)
+        {
+            return;
+        }
+        let future_trait = tcx.require_lang_item(LangItem::Future, None);
+        match peel_boxed_future(tcx, target_ty).kind() {
+            ty::Dynamic(predicates, _) if predicates.principal_def_id() == Some(future_trait) => {}
+            _ => return,
+        }
+
+        // Find where the type of the held value is written, along with the expressions whose
+        // futures get boxed into it and the context they are type-checked in: either a binding
+        // with an explicit type, or a call to a local function returning the boxed future.
+        let mut expr = hir.expect_expr(held_expr);
+        let mut written = None;
+        if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = expr.kind {
+            let local = match path.res {
+                Res::Local(hir_id) => match hir.find(hir.get_parent_node(hir_id)) {
+                    Some(Node::Local(local)) => local,
+                    _ => return,
+                },
+                _ => return,
+            };
+            match (local.ty, local.init) {
+                (Some(ty), Some(init)) => {
+                    written = Some((ty, vec![init], typeck_results, param_env))
+                }
+                (None, Some(init)) => expr = init,
+                _ => return,
+            }
+        }
+        let written = match written {
+            Some(written) => written,
+            None => {
+                let callee = match expr.kind {
+                    hir::ExprKind::Call(callee, _) => match callee.kind {
+                        hir::ExprKind::Path(ref qpath) => {
+                            typeck_results.qpath_res(qpath, callee.hir_id).opt_def_id()
+                        }
+                        _ => None,
+                    },
+                    hir::ExprKind::MethodCall(..) => {
+                        typeck_results.type_dependent_def_id(expr.hir_id)
+                    }
+                    _ => None,
+                };
+                let def_id = match callee.and_then(|def_id| def_id.as_local()) {
+                    Some(def_id) => def_id,
+                    None => return,
+                };
+                // The function might be the one being type-checked, which would be a cycle.
+                if self.in_progress_typeck_results.map_or(false, |t| t.borrow().hir_owner == def_id)
+                {
+                    return;
+                }
+                let hir_id = hir.local_def_id_to_hir_id(def_id);
+                let ret_ty = match hir.fn_decl_by_hir_id(hir_id) {
+                    Some(hir::FnDecl { output: hir::FnRetTy::Return(ty), .. }) => *ty,
+                    _ => return,
+                };
+                let body_id = match hir.maybe_body_owned_by(hir_id) {
+                    Some(body_id) => body_id,
+                    None => return,
+                };
+                let mut visitor = ReturnsVisitor::default();
+                visitor.visit_body(hir.body(body_id));
+                (ret_ty, visitor.returns, tcx.typeck(def_id), tcx.param_env(def_id))
+            }
+        };
+        let (written_ty, boxed_exprs, typeck_results, param_env) = written;
+
+        // Only suggest the bound if the futures that are boxed are known to implement the trait.
+        // Whether an opaque type implements an auto trait depends on the type-checking results of
+        // its defining body, which may be the one being type-checked, so those are skipped.
+        let boxed_futures_implement = !boxed_exprs.is_empty()
+            && boxed_exprs.iter().all(|expr| {
+                let ty = self.resolve_vars_if_possible(typeck_results.expr_ty(expr));
+                let ty = tcx.erase_regions(peel_boxed_future(tcx, ty));
+                !ty.has_infer_types()
+                    && !matches!(ty.kind(), ty::Dynamic(..) | ty::Opaque(..))
+                    && tcx.type_implements_trait((
+                        trait_ref.def_id,
+                        ty,
+                        ty::List::empty(),
+                        param_env,
+                    ))
+            });
+        if !boxed_futures_implement {
+            return;
+        }
+
+        // Look through the `Pin` and `Box` type arguments for the trait object.
+        let mut written_ty = written_ty;
+        let object_span = loop {
+            match written_ty.kind {
+                hir::TyKind::TraitObject(..) => break written_ty.span,
+                hir::TyKind::Path(hir::QPath::Resolved(None, path)) => {
+                    let args = path.segments.last().and_then(|segment| segment.args);
+                    match args.and_then(|args| args.args.first()) {
+                        Some(hir::GenericArg::Type(ty)) => written_ty = ty,
+                        _ => return,
+                    }
+                }
+                _ => return,
+            }
+        };
+        let trait_name = tcx.item_name(trait_ref.def_id);
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            object_span.shrink_to_hi(),
+            &format!("consider requiring the boxed future to be `{}`", trait_name),
+            format!(" + {}", trait_name),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::ConstrainParam);
+    }
+
     /// Unconditionally adds the diagnostic note described in
     /// `maybe_note_obligation_cause_for_async_await`'s documentation comment.
     fn note_obligation_cause_for_async_await(
@@ -1496,6 +4526,7 @@ fn note_obligation_cause_for_async_await(
         err: &mut DiagnosticBuilder<'_>,
         interior_or_upvar_span: GeneratorInteriorOrUpvar,
         interior_extra_info: Option<(Option<Span>, Span, Option<hir::HirId>, Option<Span>)>,
+        moved_upvar: Option<hir::HirId>,
         inner_generator_body: Option<&hir::Body<'tcx>>,
         outer_generator: Option<DefId>,
         trait_ref: ty::TraitRef<'tcx>,
@@ -1708,12 +4739,43 @@ fn note_obligation_cause_for_async_await(
                             ),
                         );
                     } else {
-                        // Look at the last interior type to get a span for the `.await`.
                         debug!(
                             "note_obligation_cause_for_async_await generator_interior_types: {:#?}",
                             typeck_results.generator_interior_types
                         );
                         explain_yield(interior_span, yield_span, scope_span);
+
+                        // Values with a `Drop` impl stay alive until the end of their scope, and
+                        // thus across the await. When the binding isn't used after the await,
+                        // moving it into a block of its own that ends before the await fixes that.
+                        let has_dtor =
+                            target_ty.ty_adt_def().map_or(false, |def| def.has_dtor(self.tcx));
+                        if let (Some(_), true, Some(body)) =
+                            (scope_span, has_dtor, inner_generator_body)
+                        {
+                            let mut visitor = BindingUsesVisitor {
+                                span: interior_span,
+                                after: yield_span,
+                                binding: None,
+                                used_after: false,
+                            };
+                            visitor.visit_body(body);
+                            match visitor.binding {
+                                Some((_, name)) if !visitor.used_after => {
+                                    err.note(&format!(
+                                        "`{}` implements `Drop`, so `{}` is only dropped at the \
+                                         end of its scope, after the {}",
+                                        target_ty, name, await_or_yield,
+                                    ));
+                                    err.help(&format!(
+                                        "consider declaring `{}` in an inner block that ends \
+                                         before the {}, so that it is dropped earlier",
+                                        name, await_or_yield,
+                                    ));
+                                }
+                                _ => {}
+                            }
+                        }
                     }
 
                     if let Some(expr_id) = expr {
@@ -1768,7 +4830,65 @@ fn note_obligation_cause_for_async_await(
                     upvar_span,
                     format!("has type `{}` which {}", target_ty, trait_explanation),
                 );
-                err.span_note(span, &format!("captured value {}", trait_explanation));
+                let msg = match moved_upvar {
+                    // A value moved into the generator is stored in it, and so is held across
+                    // every await or yield in its body, rather than maybe used after one.
+                    Some(upvar_id) => {
+                        let mut visitor = AwaitsVisitor::default();
+                        if let Some(body) = inner_generator_body {
+                            visitor.visit_body(body);
+                        }
+                        if is_async && !visitor.awaits.is_empty() {
+                            format!(
+                                "`{}` is moved into this future and held across the await",
+                                hir.name(upvar_id),
+                            )
+                        } else {
+                            format!(
+                                "`{}` is moved into this {}",
+                                hir.name(upvar_id),
+                                future_or_generator,
+                            )
+                        }
+                    }
+                    None => format!("captured value {}", trait_explanation),
+                };
+                err.span_note(span, &msg);
+            }
+        }
+
+        // Point out the thread-safe counterparts of the standard types that commonly make a
+        // future or generator not `Send`.
+        if is_send || is_sync {
+            let value_span = match interior_or_upvar_span {
+                GeneratorInteriorOrUpvar::Interior(span)
+                | GeneratorInteriorOrUpvar::Upvar(span) => span,
+            };
+            let mut value_ty = target_ty;
+            while let ty::Ref(_, ty, _) = *value_ty.kind() {
+                value_ty = ty;
+            }
+            if let ty::Adt(def, substs) = *value_ty.kind() {
+                let is_item = |name| self.tcx.is_diagnostic_item(name, def.did);
+                let inner_ty = substs.type_at(0);
+                let alternative = if is_item(sym::Rc) {
+                    Some((format!("`Arc<{}>`", inner_ty), "sent"))
+                } else if is_item(sym::RefCell) {
+                    Some((format!("`Mutex<{0}>` or `RwLock<{0}>`", inner_ty), "shared"))
+                } else if is_item(sym::Cell) {
+                    Some((format!("an atomic type or `Mutex<{}>`", inner_ty), "shared"))
+                } else {
+                    None
+                };
+                if let Some((alternative, verb)) = alternative {
+                    err.span_help(
+                        value_span,
+                        &format!(
+                            "consider using {}, which can be {} between threads, instead of `{}`",
+                            alternative, verb, value_ty,
+                        ),
+                    );
+                }
             }
         }
 
@@ -1778,9 +4898,11 @@ fn note_obligation_cause_for_async_await(
         self.note_obligation_cause_code(
             err,
             &obligation.predicate,
+            &obligation.cause,
             next_code.unwrap(),
             &mut Vec::new(),
             &mut Default::default(),
+            None,
         );
     }
 
@@ -1788,9 +4910,11 @@ fn note_obligation_cause_code<T>(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         predicate: &T,
+        cause: &ObligationCause<'tcx>,
         cause_code: &ObligationCauseCode<'tcx>,
-        obligated_types: &mut Vec<&ty::TyS<'tcx>>,
+        obligated_predicates: &mut Vec<ty::Predicate<'tcx>>,
         seen_requirements: &mut FxHashSet<DefId>,
+        tree_depth: Option<usize>,
     ) where
         T: fmt::Display,
     {
@@ -1831,7 +4955,7 @@ fn note_obligation_cause_code<T>(
             }
             ObligationCauseCode::ItemObligation(item_def_id) => {
                 let item_name = tcx.def_path_str(item_def_id);
-                let msg = format!("required by `{}`", item_name);
+                let msg = cause_tree_root(tree_depth, format!("required by `{}`", item_name));
                 if let Some(sp) = tcx.hir().span_if_local(item_def_id) {
                     let sp = tcx.sess.source_map().guess_head_span(sp);
                     err.span_label(sp, &msg);
@@ -1839,10 +4963,39 @@ fn note_obligation_cause_code<T>(
                     err.note(&msg);
                 }
             }
+            ObligationCauseCode::BindingObligation(item_def_id, span)
+                if span.in_derive_expansion() =>
+            {
+                // The bound was written by a `#[derive]`, so its span doesn't point at anything
+                // in the user's code. Point at the derive itself instead.
+                let expn_data = span.ctxt().outer_expn_data();
+                if let ExpnKind::Macro(_, derive_name) = expn_data.kind {
+                    err.span_label(
+                        expn_data.call_site,
+                        format!(
+                            "required by a bound in `{}` introduced by this `#[derive({})]`",
+                            tcx.def_path_str(item_def_id),
+                            derive_name,
+                        ),
+                    );
+                    err.note(&format!(
+                        "`#[derive({})]` added this bound to the code it generated",
+                        derive_name,
+                    ));
+                }
+            }
             ObligationCauseCode::BindingObligation(item_def_id, span) => {
                 let item_name = tcx.def_path_str(item_def_id);
-                let msg = format!("required by this bound in `{}`", item_name);
-                if let Some(ident) = tcx.opt_item_name(item_def_id) {
+                let msg = cause_tree_root(
+                    tree_depth,
+                    format!("required by this bound in `{}`", item_name),
+                );
+                // Items without a name in the source, like those from some macros, have no span
+                // worth pointing at.
+                let ident = tcx
+                    .opt_item_name(item_def_id)
+                    .filter(|ident| !ident.span.is_dummy() && !ident.span.is_empty());
+                if let Some(ident) = ident {
                     let sm = tcx.sess.source_map();
                     let same_line =
                         match (sm.lookup_line(ident.span.hi()), sm.lookup_line(span.lo())) {
@@ -1850,7 +5003,13 @@ fn note_obligation_cause_code<T>(
                             _ => true,
                         };
                     if !ident.span.overlaps(span) && !same_line {
-                        err.span_label(ident.span, "required by a bound in this");
+                        err.span_label(
+                            ident.span,
+                            format!(
+                                "required by a bound in this {}",
+                                tcx.def_kind(item_def_id).descr(item_def_id)
+                            ),
+                        );
                     }
                 }
                 if span != DUMMY_SP {
@@ -1858,8 +5017,30 @@ fn note_obligation_cause_code<T>(
                 } else {
                     err.note(&msg);
                 }
+                if tcx.def_kind(item_def_id) == DefKind::AssocFn {
+                    self.point_at_call_requiring_bound(err, cause, item_def_id);
+                }
+                if let Some(sealing_trait) = sealing_supertrait(tcx, item_def_id, span) {
+                    err.note(&format!(
+                        "`{}` appears to be sealed: its supertrait `{}` can't be named outside of \
+                         the `{}` crate, so `{}` can only be implemented there",
+                        item_name,
+                        tcx.def_path_str(sealing_trait),
+                        tcx.crate_name(item_def_id.krate),
+                        item_name,
+                    ));
+                }
             }
             ObligationCauseCode::ObjectCastObligation(object_ty) => {
+                // The obligation points at the expression being coerced, which for an `as` cast
+                // doesn't show what it's being cast to.
+                if let Some(body) = cause_body(tcx.hir(), cause) {
+                    let mut visitor = CastVisitor { operand_span: cause.span, cast_ty_span: None };
+                    visitor.visit_body(body);
+                    if let Some(cast_ty_span) = visitor.cast_ty_span {
+                        err.span_label(cast_ty_span, "required by this cast");
+                    }
+                }
                 err.note(&format!(
                     "required for the cast to the object type `{}`",
                     self.ty_to_string(object_ty)
@@ -1887,31 +5068,105 @@ fn note_obligation_cause_code<T>(
                 }
             }
             ObligationCauseCode::VariableType(hir_id) => {
-                let parent_node = self.tcx.hir().get_parent_node(hir_id);
-                match self.tcx.hir().find(parent_node) {
-                    Some(Node::Local(hir::Local {
-                        init: Some(hir::Expr { kind: hir::ExprKind::Index(_, _), span, .. }),
-                        ..
-                    })) => {
-                        // When encountering an assignment of an unsized trait, like
-                        // `let x = ""[..];`, provide a suggestion to borrow the initializer in
-                        // order to use have a slice instead.
-                        err.span_suggestion_verbose(
-                            span.shrink_to_lo(),
-                            "consider borrowing here",
-                            "&".to_owned(),
-                            Applicability::MachineApplicable,
-                        );
+                // Only an initializer that is itself an unsized place is worth borrowing, as
+                // borrowing a place doesn't need it to be `Sized`. Borrowing a temporary, like the
+                // result of a call, would still need it to be.
+                let init_is_unsized_place = |init: &hir::Expr<'_>| {
+                    if !matches!(
+                        init.kind,
+                        hir::ExprKind::Index(..)
+                            | hir::ExprKind::Unary(hir::UnOp::UnDeref, _)
+                            | hir::ExprKind::Field(..)
+                    ) {
+                        return false;
+                    }
+                    let init_ty = match self
+                        .in_progress_typeck_results
+                        .and_then(|t| t.borrow().expr_ty_adjusted_opt(init))
+                    {
+                        Some(ty) => self.resolve_vars_if_possible(ty),
+                        None => return false,
+                    };
+                    if init_ty.has_infer_types_or_consts() || init_ty.references_error() {
+                        return false;
+                    }
+                    let body_owner = tcx.hir().enclosing_body_owner(hir_id);
+                    let param_env = tcx.param_env(tcx.hir().local_def_id(body_owner));
+                    !init_ty.is_sized(tcx.at(init.span), param_env)
+                };
+                let parent = self.tcx.hir().find(self.tcx.hir().get_parent_node(hir_id));
+                let local_trait_object = match parent {
+                    Some(Node::Local(hir::Local { ty: Some(ty), .. })) => {
+                        written_trait_object(tcx, ty)
+                    }
+                    _ => None,
+                };
+                match parent {
+                    Some(Node::Local(hir::Local { ty, init: Some(init), .. }))
+                        if init_is_unsized_place(init) =>
+                    {
+                        // When encountering an assignment of an unsized value, like
+                        // `let x = ""[..];` or `let x = *boxed_slice;`, provide a suggestion to
+                        // borrow the initializer in order to have a reference instead.
+                        match ty {
+                            // `let x: str = *s;` would still fail after borrowing the initializer,
+                            // so the type annotation needs to become a reference as well.
+                            Some(ty) => err.multipart_suggestion(
+                                "consider borrowing here and changing the type of the binding \
+                                 accordingly",
+                                vec![
+                                    (ty.span.shrink_to_lo(), "&".to_owned()),
+                                    (init.span.shrink_to_lo(), "&".to_owned()),
+                                ],
+                                Applicability::MachineApplicable,
+                            ),
+                            None => err.span_suggestion_verbose(
+                                init.span.shrink_to_lo(),
+                                "consider borrowing here",
+                                "&".to_owned(),
+                                Applicability::MachineApplicable,
+                            ),
+                        };
+                        err.note("all local variables must have a statically known size");
+                    }
+                    // `let x: dyn Trait = value;` can box the value instead.
+                    Some(Node::Local(hir::Local { ty: Some(ty), init, .. }))
+                        if local_trait_object.is_some() =>
+                    {
+                        if let Some((trait_obj, true)) = local_trait_object {
+                            let mut parts = vec![(ty.span, format!("Box<dyn {}>", trait_obj))];
+                            if let Some(init) = init {
+                                parts.push((init.span.shrink_to_lo(), "Box::new(".to_owned()));
+                                parts.push((init.span.shrink_to_hi(), ")".to_owned()));
+                            }
+                            err.multipart_suggestion(
+                                "consider boxing the trait object so that it has a statically \
+                                 known size",
+                                parts,
+                                Applicability::MaybeIncorrect,
+                            );
+                        }
                         err.note("all local variables must have a statically known size");
                     }
                     Some(Node::Param(param)) => {
-                        err.span_suggestion_verbose(
-                            param.ty_span.shrink_to_lo(),
-                            "function arguments must have a statically known size, borrowed types \
-                            always have a known size",
-                            "&".to_owned(),
-                            Applicability::MachineApplicable,
-                        );
+                        let dyn_param =
+                            enclosing_fn_sig(tcx, hir_id).and_then(|(decl, body, sig_owner)| {
+                                let index =
+                                    body.params.iter().position(|p| p.hir_id == param.hir_id)?;
+                                Some((decl.inputs.get(index)?, sig_owner))
+                            });
+                        let suggested = dyn_param.map_or(false, |(ty, sig_owner)| {
+                            suggest_borrowing_trait_object_param(tcx, err, ty, sig_owner)
+                        });
+                        if !suggested {
+                            err.span_suggestion_verbose(
+                                param.ty_span.shrink_to_lo(),
+                                "function arguments must have a statically known size, borrowed \
+                                 types always have a known size",
+                                "&".to_owned(),
+                                Applicability::MachineApplicable,
+                            );
+                        }
                     }
                     _ => {
                         err.note("all local variables must have a statically known size");
@@ -1922,24 +5177,76 @@ fn note_obligation_cause_code<T>(
                 }
             }
             ObligationCauseCode::SizedArgumentType(sp) => {
-                if let Some(span) = sp {
-                    err.span_suggestion_verbose(
-                        span.shrink_to_lo(),
-                        "function arguments must have a statically known size, borrowed types \
-                         always have a known size",
-                        "&".to_string(),
-                        Applicability::MachineApplicable,
-                    );
-                } else {
-                    err.note("all function arguments must have a statically known size");
+                // The span is that of either the parameter's pattern or its type.
+                let dyn_param = sp.and_then(|span| {
+                    let (decl, body, sig_owner) = enclosing_fn_sig(tcx, cause.body_id)?;
+                    let index = body
+                        .params
+                        .iter()
+                        .position(|param| param.pat.span == span || param.ty_span == span)?;
+                    Some((decl.inputs.get(index)?, sig_owner))
+                });
+                let suggested = dyn_param.map_or(false, |(ty, sig_owner)| {
+                    suggest_borrowing_trait_object_param(tcx, err, ty, sig_owner)
+                });
+                match sp {
+                    _ if suggested => {}
+                    Some(span) => {
+                        err.span_suggestion_verbose(
+                            span.shrink_to_lo(),
+                            "function arguments must have a statically known size, borrowed \
+                             types always have a known size",
+                            "&".to_string(),
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                    None => {
+                        err.note("all function arguments must have a statically known size");
+                    }
                 }
                 if tcx.sess.opts.unstable_features.is_nightly_build()
                     && !self.tcx.features().unsized_fn_params
                 {
                     err.help("unsized fn params are gated as an unstable feature");
                 }
-            }
-            ObligationCauseCode::SizedReturnType => {
+            }
+            ObligationCauseCode::SizedReturnType => {
+                // Returning `dyn Trait` from a free function is handled by `suggest_impl_trait`,
+                // which only gets here when some returned value doesn't implement the trait.
+                let fn_hir_id = tcx.hir().get_parent_node(cause.body_id);
+                let is_method = !matches!(tcx.hir().find(fn_hir_id), Some(Node::Item(_)));
+                let ret_trait_object = enclosing_fn_sig(tcx, cause.body_id)
+                    .filter(|_| is_method)
+                    .and_then(|(decl, _, sig_owner)| match decl.output {
+                        hir::FnRetTy::Return(ret_ty) if ret_ty.span == cause.span => {
+                            Some((ret_ty.span, written_trait_object(tcx, ret_ty)?, sig_owner))
+                        }
+                        _ => None,
+                    });
+                match ret_trait_object {
+                    // The signature of a trait impl method has to keep matching the trait's.
+                    Some((_, _, FnSigOwner::TraitImpl)) => {}
+                    Some((span, (trait_obj, is_object_safe), FnSigOwner::Free)) => {
+                        suggest_trait_object_return_type_alternatives(
+                            err,
+                            span,
+                            &trait_obj,
+                            is_object_safe,
+                        );
+                    }
+                    Some((span, (trait_obj, true), _)) => {
+                        err.span_suggestion(
+                            span,
+                            &format!(
+                                "use a boxed trait object if all return paths implement trait `{}`",
+                                trait_obj,
+                            ),
+                            format!("Box<dyn {}>", trait_obj),
+                            Applicability::MaybeIncorrect,
+                        );
+                    }
+                    _ => {}
+                }
                 err.note("the return type of a function must have a statically known size");
             }
             ObligationCauseCode::SizedYieldType => {
@@ -2030,22 +5337,31 @@ fn note_obligation_cause_code<T>(
                 };
 
                 // Don't print the tuple of capture types
+                let mut tree_depth = tree_depth;
                 if !is_upvar_tys_infer_tuple {
-                    err.note(&format!("required because it appears within the type `{}`", ty));
+                    err.note(&cause_tree_step(
+                        tree_depth,
+                        format!("required because it appears within the type `{}`", ty),
+                    ));
+                    tree_depth = tree_depth.map(|depth| depth + 1);
                 }
 
-                obligated_types.push(ty);
-
+                // Track the whole requirement rather than just the type, as the same type can be
+                // required to implement different traits further up the chain.
                 let parent_predicate = parent_trait_ref.without_const().to_predicate(tcx);
-                if !self.is_recursive_obligation(obligated_types, &data.parent_code) {
+                obligated_predicates.push(parent_predicate);
+
+                if !self.is_recursive_obligation(obligated_predicates, &data.parent_code) {
                     // #74711: avoid a stack overflow
                     ensure_sufficient_stack(|| {
                         self.note_obligation_cause_code(
                             err,
                             &parent_predicate,
+                            cause,
                             &data.parent_code,
-                            obligated_types,
+                            obligated_predicates,
                             seen_requirements,
+                            tree_depth,
                         )
                     });
                 }
@@ -2053,11 +5369,8 @@ fn note_obligation_cause_code<T>(
             ObligationCauseCode::ImplDerivedObligation(ref data) => {
                 let mut parent_trait_ref = self.resolve_vars_if_possible(data.parent_trait_ref);
                 let parent_def_id = parent_trait_ref.def_id();
-                err.note(&format!(
-                    "required because of the requirements on the impl of `{}` for `{}`",
-                    parent_trait_ref.print_only_trait_path(),
-                    parent_trait_ref.skip_binder().self_ty()
-                ));
+                note_impl_requirements(err, parent_trait_ref, tree_depth);
+                let mut tree_depth = tree_depth.map(|depth| depth + 1);
 
                 let mut parent_predicate = parent_trait_ref.without_const().to_predicate(tcx);
                 let mut data = data;
@@ -2077,22 +5390,29 @@ fn note_obligation_cause_code<T>(
                 }
                 if count > 0 {
                     err.note(&format!("{} redundant requirements hidden", count));
-                    err.note(&format!(
-                        "required because of the requirements on the impl of `{}` for `{}`",
-                        parent_trait_ref.print_only_trait_path(),
-                        parent_trait_ref.skip_binder().self_ty()
-                    ));
+                    // This closes off the hidden requirements, so it is noted even if it repeats
+                    // an earlier note.
+                    err.note(&cause_tree_step(tree_depth, impl_requirements_msg(parent_trait_ref)));
+                    tree_depth = tree_depth.map(|depth| depth + 1);
+                }
+
+                // Mutually recursive impls can require the same trait for the same type again
+                // further down the chain. Stop once that happens, so the cycle is only noted once.
+                obligated_predicates.push(parent_predicate);
+                if !self.is_recursive_obligation(obligated_predicates, &data.parent_code) {
+                    // #74711: avoid a stack overflow
+                    ensure_sufficient_stack(|| {
+                        self.note_obligation_cause_code(
+                            err,
+                            &parent_predicate,
+                            cause,
+                            &data.parent_code,
+                            obligated_predicates,
+                            seen_requirements,
+                            tree_depth,
+                        )
+                    });
                 }
-                // #74711: avoid a stack overflow
-                ensure_sufficient_stack(|| {
-                    self.note_obligation_cause_code(
-                        err,
-                        &parent_predicate,
-                        &data.parent_code,
-                        obligated_types,
-                        seen_requirements,
-                    )
-                });
             }
             ObligationCauseCode::DerivedObligation(ref data) => {
                 let parent_trait_ref = self.resolve_vars_if_possible(data.parent_trait_ref);
@@ -2102,18 +5422,28 @@ fn note_obligation_cause_code<T>(
                     self.note_obligation_cause_code(
                         err,
                         &parent_predicate,
+                        cause,
                         &data.parent_code,
-                        obligated_types,
+                        obligated_predicates,
                         seen_requirements,
+                        tree_depth,
                     )
                 });
             }
-            ObligationCauseCode::CompareImplMethodObligation { .. } => {
-                err.note(&format!(
+            ObligationCauseCode::CompareImplMethodObligation { trait_item_def_id, .. } => {
+                let msg = format!(
                     "the requirement `{}` appears on the impl method but not on the corresponding \
                      trait method",
                     predicate
-                ));
+                );
+                match tcx.hir().span_if_local(trait_item_def_id) {
+                    Some(span) => {
+                        err.span_note(tcx.sess.source_map().guess_head_span(span), &msg);
+                    }
+                    None => {
+                        err.note(&msg);
+                    }
+                }
             }
             ObligationCauseCode::CompareImplTypeObligation { .. } => {
                 err.note(&format!(
@@ -2141,6 +5471,163 @@ trait method",
         }
     }
 
+    fn point_at_call_requiring_bound(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        cause: &ObligationCause<'tcx>,
+        method_def_id: DefId,
+    ) {
+        let typeck_results = match self.in_progress_typeck_results {
+            Some(typeck_results) => typeck_results.borrow(),
+            None => return,
+        };
+        let body = match cause_body(self.tcx.hir(), cause) {
+            Some(body) => body,
+            None => return,
+        };
+        let mut visitor = MethodCallVisitor {
+            typeck_results: &typeck_results,
+            method_def_id,
+            span: cause.span,
+            callee_span: None,
+        };
+        visitor.visit_body(body);
+        let callee_span = match visitor.callee_span {
+            Some(span) => span,
+            None => return,
+        };
+        // Don't point at calls the user didn't write, or that the error already points at.
+        if callee_span.from_expansion()
+            || err.span.primary_spans().iter().any(|span| span.overlaps(callee_span))
+        {
+            return;
+        }
+        err.span_label(
+            callee_span,
+            format!(
+                "required by a bound introduced by this call to `{}`",
+                self.tcx.def_path_str(method_def_id),
+            ),
+        );
+    }
+
+    fn suggest_moving_impl_method_where_bound(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        predicate: ty::Predicate<'tcx>,
+        impl_item_def_id: DefId,
+        trait_item_def_id: DefId,
+    ) {
+        let tcx = self.tcx;
+        let trait_pred = match predicate.bound_atom().skip_binder() {
+            ty::PredicateAtom::Trait(trait_pred, _) => trait_pred,
+            _ => return,
+        };
+        // Only the method's own type parameters have a counterpart on the trait method.
+        let param = match *trait_pred.self_ty().kind() {
+            ty::Param(param) => param,
+            _ => return,
+        };
+        let impl_ty_generics = tcx.generics_of(impl_item_def_id);
+        let trait_ty_generics = tcx.generics_of(trait_item_def_id);
+        let trait_param = match (param.index as usize).checked_sub(impl_ty_generics.parent_count) {
+            Some(own_index) if own_index < trait_ty_generics.params.len() => {
+                trait_ty_generics.param_at(trait_ty_generics.parent_count + own_index, tcx)
+            }
+            _ => return,
+        };
+        if !matches!(trait_param.kind, ty::GenericParamDefKind::Type { .. }) {
+            return;
+        }
+        let hir = tcx.hir();
+        let (impl_generics, trait_generics) = match (
+            hir.get_if_local(impl_item_def_id).and_then(|node| node.generics()),
+            hir.get_if_local(trait_item_def_id).and_then(|node| node.generics()),
+        ) {
+            (Some(impl_generics), Some(trait_generics)) => (impl_generics, trait_generics),
+            _ => return,
+        };
+
+        let predicates = impl_generics.where_clause.predicates;
+        let found = predicates.iter().enumerate().find_map(|(i, predicate)| match predicate {
+            hir::WherePredicate::BoundPredicate(bound_predicate) => {
+                match &bound_predicate.bounded_ty.kind {
+                    hir::TyKind::Path(hir::QPath::Resolved(None, path))
+                        if matches!(path.res, Res::Def(DefKind::TyParam, _))
+                            && path.segments.len() == 1
+                            && path.segments[0].ident.name == param.name => {}
+                    _ => return None,
+                }
+                let j = bound_predicate.bounds.iter().position(|bound| {
+                    bound.trait_ref().and_then(|trait_ref| trait_ref.trait_def_id())
+                        == Some(trait_pred.def_id())
+                })?;
+                Some((i, bound_predicate.bounds, j))
+            }
+            _ => None,
+        });
+        let (i, bounds, j) = match found {
+            Some(found) => found,
+            None => return,
+        };
+        let removal_span = if bounds.len() > 1 {
+            // Split the bound off of the rest of the predicate.
+            if j == 0 {
+                bounds[0].span().to(bounds[1].span().shrink_to_lo())
+            } else {
+                bounds[j - 1].span().shrink_to_hi().to(bounds[j].span())
+            }
+        } else if predicates.len() > 1 {
+            if i == 0 {
+                predicates[0].span().to(predicates[1].span().shrink_to_lo())
+            } else {
+                predicates[i - 1].span().shrink_to_hi().to(predicates[i].span())
+            }
+        } else {
+            // Remove the whole `where` clause, along with the whitespace before it.
+            let span = impl_generics.where_clause.span;
+            let prev_source = match tcx.sess.source_map().span_to_prev_source(span) {
+                Ok(prev_source) => prev_source,
+                Err(_) => return,
+            };
+            let whitespace = prev_source.len() - prev_source.trim_end().len();
+            span.with_lo(span.lo() - BytePos(whitespace as u32))
+        };
+
+        // A bound with generic arguments may name the impl method's other parameters, which
+        // the trait method knows under different names.
+        if trait_pred.trait_ref.substs.len() == 1 {
+            let where_clause = &trait_generics.where_clause;
+            let suggestions_len = err.suggestions.len();
+            err.span_suggestion_verbose(
+                where_clause.tail_span_for_suggestion(),
+                &format!(
+                    "consider requiring `{}: {}` on the trait method as well",
+                    trait_param.name,
+                    trait_pred.trait_ref.print_only_trait_path(),
+                ),
+                format!(
+                    "{} {}: {}",
+                    if where_clause.predicates.is_empty() { " where" } else { "," },
+                    trait_param.name,
+                    trait_pred.trait_ref.print_only_trait_path(),
+                ),
+                Applicability::MaybeIncorrect,
+            );
+            err.categorize_suggestions_since(suggestions_len, SuggestionCategory::ConstrainParam);
+        }
+        err.span_suggestion_verbose(
+            removal_span,
+            "or remove the requirement from the impl method",
+            String::new(),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    fn terse_suggestions(&self) -> bool {
+        self.tcx.sess.opts.debugging_opts.terse_trait_suggestions
+    }
+
     fn suggest_new_overflow_limit(&self, err: &mut DiagnosticBuilder<'_>) {
         let current_limit = self.tcx.sess.recursion_limit();
         let suggested_limit = current_limit * 2;
@@ -2230,18 +5717,400 @@ fn suggest_await_before_try(
                 if self.predicate_may_hold(&try_obligation) && impls_future {
                     if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
                         if snippet.ends_with('?') {
+                            let suggestions_len = err.suggestions.len();
                             err.span_suggestion_verbose(
                                 span.with_hi(span.hi() - BytePos(1)).shrink_to_hi(),
                                 "consider `await`ing on the `Future`",
                                 ".await".to_string(),
                                 Applicability::MaybeIncorrect,
                             );
+                            err.categorize_suggestions_since(
+                                suggestions_len,
+                                SuggestionCategory::Access,
+                            );
                         }
                     }
                 }
             }
         }
     }
+
+    fn suggest_question_mark_on_result(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let tcx = self.tcx;
+        let span = obligation.cause.span;
+        if !points_at_arg || span.from_expansion() {
+            return;
+        }
+        let self_ty = match self.resolve_vars_if_possible(trait_ref.self_ty()).no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() => ty,
+            _ => return,
+        };
+        let (def, substs) = match self_ty.kind() {
+            ty::Adt(def, substs) => (def, substs),
+            _ => return,
+        };
+        let is_result = tcx.is_diagnostic_item(sym::result_type, def.did);
+        if !is_result && !tcx.is_diagnostic_item(sym::option_type, def.did) {
+            return;
+        }
+
+        // The bound has to hold for the value `?` gets out of the `Result` or `Option`.
+        let inner_obligation = self.mk_trait_obligation_with_new_self_ty(
+            obligation.param_env,
+            trait_ref,
+            substs.type_at(0),
+        );
+        if !self.predicate_must_hold_modulo_regions(&inner_obligation) {
+            return;
+        }
+
+        // `?` has to be able to return from the enclosing function. `async fn`s and blocks are
+        // left to `suggest_await_before_try`.
+        let hir = tcx.hir();
+        let item_id = hir.get_parent_node(obligation.cause.body_id);
+        match cause_body(hir, &obligation.cause) {
+            Some(body) if body.generator_kind.is_none() => {}
+            _ => return,
+        }
+        match hir.find(item_id) {
+            Some(Node::Item(hir::Item { kind: hir::ItemKind::Fn(..), .. }))
+            | Some(Node::ImplItem(hir::ImplItem { kind: hir::ImplItemKind::Fn(..), .. }))
+            | Some(Node::TraitItem(hir::TraitItem {
+                kind: hir::TraitItemKind::Fn(_, hir::TraitFn::Provided(_)),
+                ..
+            })) => {}
+            _ => return,
+        }
+        let ret_ty = match tcx.fn_sig(hir.local_def_id(item_id)).output().no_bound_vars() {
+            Some(ret_ty) => ret_ty,
+            None => return,
+        };
+        let ret_substs = match ret_ty.kind() {
+            ty::Adt(ret_def, ret_substs) if ret_def.did == def.did => ret_substs,
+            _ => return,
+        };
+        if is_result {
+            // The error is converted with `From` when it is propagated.
+            let from_trait = match tcx.get_diagnostic_item(sym::from_trait) {
+                Some(from_trait) => from_trait,
+                None => return,
+            };
+            let from_trait_ref = ty::TraitRef::new(
+                from_trait,
+                tcx.mk_substs_trait(ret_substs.type_at(1), &[substs.type_at(1).into()]),
+            );
+            let from_obligation = Obligation::new(
+                ObligationCause::dummy(),
+                obligation.param_env,
+                from_trait_ref.without_const().to_predicate(tcx),
+            );
+            if !self.predicate_must_hold_modulo_regions(&from_obligation) {
+                return;
+            }
+        }
+
+        let msg = if is_result {
+            "consider using `?` to propagate the error and use the `Ok` value"
+        } else {
+            "consider using `?` to return early on `None` and use the `Some` value"
+        };
+        // `?` binds tighter than binary operators, casts and borrows, so `a + b` becomes
+        // `(a + b)?` rather than `a + b?`.
+        let arg = cause_expr(hir, &obligation.cause);
+        let suggestions_len = err.suggestions.len();
+        if arg.map_or(false, |arg| arg.precedence().order() < PREC_POSTFIX) {
+            err.multipart_suggestion(
+                msg,
+                vec![
+                    (span.shrink_to_lo(), "(".to_string()),
+                    (span.shrink_to_hi(), ")?".to_string()),
+                ],
+                Applicability::MaybeIncorrect,
+            );
+        } else {
+            err.span_suggestion_verbose(
+                span.shrink_to_hi(),
+                msg,
+                "?".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Access);
+    }
+
+    /// Futures that aren't `Unpin` have to be pinned before they can be polled. When a `Future`
+    /// bound fails on a borrowed future because it isn't `Unpin`, suggest pinning it on the heap
+    /// with `Box::pin`. Futures that are `Unpin` don't need to be pinned: passing them by value,
+    /// which `suggest_remove_reference` suggests, is enough.
+    fn suggest_pinning(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let tcx = self.tcx;
+        let span = obligation.cause.span;
+        if !points_at_arg || span.from_expansion() {
+            return;
+        }
+        let lang_items = tcx.lang_items();
+        let (pin_type, unpin_trait, future_trait) =
+            match (lang_items.pin_type(), lang_items.unpin_trait(), lang_items.future_trait()) {
+                (Some(pin_type), Some(unpin_trait), Some(future_trait)) => {
+                    (pin_type, unpin_trait, future_trait)
+                }
+                _ => return,
+            };
+        // `&mut F: Future` fails on `F: Unpin`, possibly because of one of `F`'s fields, but it is
+        // `F` that has to be pinned to be a `Future`.
+        let mut trait_ref = trait_ref;
+        let mut code = &obligation.cause.code;
+        while let ObligationCauseCode::BuiltinDerivedObligation(data) = code {
+            code = &data.parent_code;
+        }
+        if let ObligationCauseCode::ImplDerivedObligation(data) = code {
+            if data.parent_trait_ref.def_id() == future_trait {
+                trait_ref = data.parent_trait_ref;
+            }
+        }
+        // `Pin<Box<T>>` is `Unpin` whatever `T` is, so other bounds can't be met by pinning.
+        if trait_ref.def_id() != future_trait {
+            return;
+        }
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        let self_ty = match trait_ref.self_ty().no_bound_vars() {
+            Some(ty) if !ty.has_infer_types_or_consts() => ty,
+            _ => return,
+        };
+        let snippet = match tcx.sess.source_map().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        // Pin the borrowed value rather than the borrow.
+        let (ty, value) = match (self_ty.kind(), snippet.strip_prefix('&')) {
+            (ty::Ref(_, ty, _), Some(value)) => {
+                let value = value.trim_start();
+                (*ty, value.strip_prefix("mut ").map_or(value, |value| value.trim_start()))
+            }
+            _ => (self_ty, &snippet[..]),
+        };
+
+        let is_unpin = tcx.type_implements_trait((
+            unpin_trait,
+            tcx.erase_regions(ty),
+            ty::List::empty(),
+            obligation.param_env,
+        ));
+        if is_unpin {
+            return;
+        }
+        let pinned = tcx.mk_adt(tcx.adt_def(pin_type), tcx.intern_substs(&[tcx.mk_box(ty).into()]));
+        let obligation =
+            self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, pinned);
+        if !self.predicate_must_hold_modulo_regions(&obligation) {
+            return;
+        }
+        let suggestions_len = err.suggestions.len();
+        err.span_suggestion_verbose(
+            span,
+            "consider pinning the value on the heap with `Box::pin`",
+            format!("Box::pin({})", value),
+            Applicability::MaybeIncorrect,
+        );
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Convert);
+    }
+
+    /// When a bound fails on a `Mutex`, `RwLock` or `RefCell` but holds for the guard that gives
+    /// access to the value inside it, suggest getting that guard first: `m` =>
+    /// `m.lock().unwrap()`, `c` => `c.borrow()`.
+    fn suggest_accessing_guarded_value(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let tcx = self.tcx;
+        let span = obligation.cause.span;
+        if !points_at_arg || span.from_expansion() {
+            return;
+        }
+        // The guards are no more thread-safe than the containers themselves.
+        if tcx.trait_is_auto(trait_ref.def_id()) {
+            return;
+        }
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        let (def, substs) = match trait_ref.self_ty().no_bound_vars().map(|ty| ty.kind()) {
+            Some(&ty::Adt(def, substs)) if !substs.has_infer_types_or_consts() => (def, substs),
+            _ => return,
+        };
+        let is_item = |name| tcx.is_diagnostic_item(name, def.did);
+        let (msg, sugg, guard) = if is_item(sym::mutex_type) {
+            (
+                "consider locking the `Mutex` to use the value it guards",
+                ".lock().unwrap()",
+                sym::MutexGuard,
+            )
+        } else if is_item(sym::RwLock) {
+            (
+                "consider acquiring a read lock on the `RwLock` to use the value it guards",
+                ".read().unwrap()",
+                sym::RwLockReadGuard,
+            )
+        } else if is_item(sym::RefCell) {
+            ("consider borrowing the value inside the `RefCell`", ".borrow()", sym::RefCellRef)
+        } else {
+            return;
+        };
+
+        // The guard only forwards some traits, like `Display`, to the value it gives access to.
+        let guard = match tcx.get_diagnostic_item(guard) {
+            Some(guard) => guard,
+            None => return,
+        };
+        let guard_ty = tcx.mk_adt(
+            tcx.adt_def(guard),
+            tcx.intern_substs(&[tcx.lifetimes.re_static.into(), substs.type_at(0).into()]),
+        );
+        let obligation =
+            self.mk_trait_obligation_with_new_self_ty(obligation.param_env, trait_ref, guard_ty);
+        if !self.predicate_must_hold_modulo_regions(&obligation) {
+            return;
+        }
+        let suggestions_len = err.suggestions.len();
+        // The method call binds tighter than a dereference or a binary operator, so `*boxed`
+        // becomes `(*boxed).lock().unwrap()`.
+        let arg = cause_expr(tcx.hir(), &obligation.cause);
+        if arg.map_or(false, |arg| arg.precedence().order() < PREC_POSTFIX) {
+            err.multipart_suggestion(
+                msg,
+                vec![
+                    (span.shrink_to_lo(), "(".to_string()),
+                    (span.shrink_to_hi(), format!("){}", sugg)),
+                ],
+                Applicability::MaybeIncorrect,
+            );
+        } else {
+            err.span_suggestion_verbose(
+                span.shrink_to_hi(),
+                msg,
+                sugg.to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Access);
+    }
+
+    /// When a bound fails on a struct but holds for exactly one of its fields, the struct is
+    /// likely a newtype that the user forgot to unwrap: suggest `value.0` or `value.field`.
+    fn suggest_accessing_field(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+        trait_ref: ty::Binder<ty::TraitRef<'tcx>>,
+        points_at_arg: bool,
+    ) {
+        let tcx = self.tcx;
+        let span = obligation.cause.span;
+        if !points_at_arg || span.from_expansion() || !err.suggestions.is_empty() {
+            return;
+        }
+        if tcx.trait_is_auto(trait_ref.def_id()) {
+            return;
+        }
+        let trait_ref = self.resolve_vars_if_possible(trait_ref);
+        let self_ty = match trait_ref.self_ty().no_bound_vars() {
+            Some(ty) => ty,
+            None => return,
+        };
+        // Appending the field to `&value` gives `&value.field`, so a borrow written out in the
+        // argument is kept around the field.
+        let is_borrow = tcx
+            .sess
+            .source_map()
+            .span_to_snippet(span)
+            .map_or(false, |snippet| snippet.starts_with('&'));
+        let (adt_ty, borrow) = match *self_ty.kind() {
+            ty::Ref(region, ty, mutbl) if is_borrow => (ty, Some((region, mutbl))),
+            _ => (self_ty, None),
+        };
+        let (def, substs) = match *adt_ty.kind() {
+            ty::Adt(def, substs) if def.is_struct() && !substs.has_infer_types_or_consts() => {
+                (def, substs)
+            }
+            _ => return,
+        };
+        if !is_borrow && def.has_dtor(tcx) {
+            // Fields can't be moved out of types that implement `Drop`.
+            return;
+        }
+
+        let module = tcx.parent_module(obligation.cause.body_id).to_def_id();
+        let mut matching_fields = def.non_enum_variant().fields.iter().filter(|field| {
+            if !field.vis.is_accessible_from(module, tcx) {
+                return false;
+            }
+            let field_ty = field.ty(tcx, substs);
+            let field_ty = match borrow {
+                Some((region, mutbl)) => tcx.mk_ref(region, TypeAndMut { ty: field_ty, mutbl }),
+                None => field_ty,
+            };
+            let obligation = self.mk_trait_obligation_with_new_self_ty(
+                obligation.param_env,
+                trait_ref,
+                field_ty,
+            );
+            self.predicate_must_hold_modulo_regions(&obligation)
+        });
+        // Don't guess between several fields that would all do.
+        let field = match (matching_fields.next(), matching_fields.next()) {
+            (Some(field), None) => field,
+            _ => return,
+        };
+        let msg = format!(
+            "consider accessing the `{}` field, which implements `{}`",
+            field.ident,
+            trait_ref.print_only_trait_path(),
+        );
+        // The field access binds tighter than anything but a place or a call, so `*value` and
+        // `a + b` have to be parenthesized before the field can be appended to them.
+        let arg = cause_expr(tcx.hir(), &obligation.cause);
+        let value = match arg.map(|arg| &arg.kind) {
+            Some(hir::ExprKind::AddrOf(hir::BorrowKind::Ref, _, value)) if is_borrow => {
+                Some(*value)
+            }
+            _ => arg,
+        };
+        let suggestions_len = err.suggestions.len();
+        match value {
+            Some(value) if value.precedence().order() < PREC_POSTFIX => {
+                err.multipart_suggestion(
+                    &msg,
+                    vec![
+                        (value.span.shrink_to_lo(), "(".to_string()),
+                        (value.span.shrink_to_hi(), format!(").{}", field.ident)),
+                    ],
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            _ => {
+                err.span_suggestion_verbose(
+                    span.shrink_to_hi(),
+                    &msg,
+                    format!(".{}", field.ident),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+        err.categorize_suggestions_since(suggestions_len, SuggestionCategory::Access);
+    }
 }
 
 /// Collect all the returned expressions within the input expression.
@@ -2302,6 +6171,37 @@ fn visit_body(&mut self, body: &'v hir::Body<'v>) {
     }
 }
 
+/// Peels the `Pin` and `Box` layers off of a boxed future type: `Pin<Box<T>>` → `T`.
+fn peel_boxed_future<'tcx>(tcx: TyCtxt<'tcx>, mut ty: Ty<'tcx>) -> Ty<'tcx> {
+    let pin_type = tcx.lang_items().pin_type();
+    loop {
+        match *ty.kind() {
+            ty::Adt(def, substs) if def.is_box() || Some(def.did) == pin_type => {
+                ty = substs.type_at(0)
+            }
+            _ => return ty,
+        }
+    }
+}
+
+/// Finds the expression awaited in `body` whose type, after adjustments, satisfies `matches`,
+/// which is where the future of that type is held across an await.
+fn find_awaited_expr<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &hir::Body<'_>,
+    typeck_results: &TypeckResults<'tcx>,
+    matches: impl Fn(Ty<'tcx>) -> bool,
+) -> Option<&'tcx hir::Expr<'tcx>> {
+    let mut visitor = AwaitsVisitor::default();
+    visitor.visit_body(body);
+    debug!("find_awaited_expr: awaits = {:?}", visitor.awaits);
+    visitor
+        .awaits
+        .into_iter()
+        .map(|id| tcx.hir().expect_expr(id))
+        .find(|await_expr| matches(typeck_results.expr_ty_adjusted(await_expr)))
+}
+
 /// Collect all the awaited expressions within the input expression.
 #[derive(Default)]
 struct AwaitsVisitor {
@@ -2323,6 +6223,303 @@ fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
     }
 }
 
+/// Find the type of the `as` cast whose operand is at `operand_span`.
+struct CastVisitor {
+    operand_span: Span,
+    cast_ty_span: Option<Span>,
+}
+
+impl<'v> Visitor<'v> for CastVisitor {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if let hir::ExprKind::Cast(operand, ty) = ex.kind {
+            if operand.span == self.operand_span {
+                self.cast_ty_span = Some(ty.span);
+            }
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Collect the generic arguments of every path to the type `def_id` written as a type.
+struct AdtPathVisitor<'v> {
+    def_id: DefId,
+    args: Vec<&'v hir::GenericArgs<'v>>,
+}
+
+impl<'v> Visitor<'v> for AdtPathVisitor<'v> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_ty(&mut self, ty: &'v hir::Ty<'v>) {
+        if let hir::TyKind::Path(hir::QPath::Resolved(None, path)) = ty.kind {
+            if let (Res::Def(_, def_id), Some(segment)) = (path.res, path.segments.last()) {
+                if def_id == self.def_id && !ty.span.from_expansion() {
+                    self.args.push(segment.args());
+                }
+            }
+        }
+        hir::intravisit::walk_ty(self, ty);
+    }
+}
+
+/// The body of the item an obligation with `cause` was registered in, if it has one.
+fn cause_body<'hir>(
+    hir: rustc_middle::hir::map::Map<'hir>,
+    cause: &ObligationCause<'_>,
+) -> Option<&'hir hir::Body<'hir>> {
+    hir.maybe_body_owned_by(hir.get_parent_node(cause.body_id)).map(|body_id| hir.body(body_id))
+}
+
+/// The expression an obligation with `cause` points at, if it is in a body.
+fn cause_expr<'hir>(
+    hir: rustc_middle::hir::map::Map<'hir>,
+    cause: &ObligationCause<'_>,
+) -> Option<&'hir hir::Expr<'hir>> {
+    let mut visitor = ExprAtSpanVisitor { span: cause.span, expr: None };
+    visitor.visit_body(cause_body(hir, cause)?);
+    visitor.expr
+}
+
+/// Find the expression at `span`.
+struct ExprAtSpanVisitor<'v> {
+    span: Span,
+    expr: Option<&'v hir::Expr<'v>>,
+}
+
+impl<'v> Visitor<'v> for ExprAtSpanVisitor<'v> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if ex.span == self.span {
+            self.expr = Some(ex);
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Find the arguments of the call whose callee is at `callee_span`.
+struct CallArgsVisitor<'v> {
+    callee_span: Span,
+    args: &'v [hir::Expr<'v>],
+}
+
+impl<'v> Visitor<'v> for CallArgsVisitor<'v> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        match ex.kind {
+            hir::ExprKind::Call(callee, args) if callee.span == self.callee_span => {
+                self.args = args;
+            }
+            hir::ExprKind::MethodCall(segment, _, args, _)
+                if segment.ident.span == self.callee_span =>
+            {
+                self.args = args;
+            }
+            _ => {}
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Find the innermost call to `method_def_id` that contains `span`, and record the span of its
+/// callee.
+struct MethodCallVisitor<'a, 'tcx> {
+    typeck_results: &'a TypeckResults<'tcx>,
+    method_def_id: DefId,
+    span: Span,
+    callee_span: Option<Span>,
+}
+
+impl<'v, 'a, 'tcx> Visitor<'v> for MethodCallVisitor<'a, 'tcx> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if ex.span.contains(self.span) {
+            match ex.kind {
+                hir::ExprKind::Call(callee, _) => {
+                    if let Some(ty) = self.typeck_results.node_type_opt(callee.hir_id) {
+                        if let ty::FnDef(def_id, _) = *ty.kind() {
+                            if def_id == self.method_def_id {
+                                self.callee_span = Some(callee.span);
+                            }
+                        }
+                    }
+                }
+                hir::ExprKind::MethodCall(segment, ..) => {
+                    if self.typeck_results.type_dependent_def_id(ex.hir_id)
+                        == Some(self.method_def_id)
+                    {
+                        self.callee_span = Some(segment.ident.span);
+                    }
+                }
+                _ => {}
+            }
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Collect all the `let` bindings without a type annotation within a body.
+#[derive(Default)]
+struct UnannotatedLocalsVisitor<'v> {
+    locals: Vec<&'v hir::Local<'v>>,
+}
+
+impl<'v> Visitor<'v> for UnannotatedLocalsVisitor<'v> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_local(&mut self, local: &'v hir::Local<'v>) {
+        if local.ty.is_none() {
+            self.locals.push(local);
+        }
+        hir::intravisit::walk_local(self, local)
+    }
+}
+
+/// Find the binding declared by the pattern at `span` within a body, and whether it is used
+/// anywhere after `after`.
+struct BindingUsesVisitor {
+    span: Span,
+    after: Span,
+    binding: Option<(hir::HirId, Ident)>,
+    used_after: bool,
+}
+
+impl<'v> Visitor<'v> for BindingUsesVisitor {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_pat(&mut self, pat: &'v hir::Pat<'v>) {
+        if let hir::PatKind::Binding(_, hir_id, ident, _) = pat.kind {
+            if pat.span == self.span {
+                self.binding = Some((hir_id, ident));
+            }
+        }
+        hir::intravisit::walk_pat(self, pat)
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = ex.kind {
+            if let (Res::Local(hir_id), Some((binding, _))) = (path.res, self.binding) {
+                if hir_id == binding && ex.span.lo() >= self.after.hi() {
+                    self.used_after = true;
+                }
+            }
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Collect all the method calls named `name` within a body, along with their receivers.
+struct NamedMethodCallsVisitor<'v> {
+    name: Symbol,
+    calls: Vec<(&'v hir::PathSegment<'v>, &'v hir::Expr<'v>, &'v hir::Expr<'v>)>,
+}
+
+impl<'v> Visitor<'v> for NamedMethodCallsVisitor<'v> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if let hir::ExprKind::MethodCall(segment, _, [receiver, ..], _) = ex.kind {
+            if segment.ident.name == self.name {
+                self.calls.push((segment, ex, receiver));
+            }
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
+/// Find a tuple type within `span` with an element of type `ty` anywhere but in the last
+/// position, the only one where an unsized type is allowed.
+struct UnsizedTupleElemVisitor<'v, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    span: Span,
+    ty: Ty<'tcx>,
+    found: Option<(&'v [hir::Ty<'v>], usize)>,
+}
+
+impl<'v, 'tcx> UnsizedTupleElemVisitor<'v, 'tcx> {
+    /// We can't lower HIR types here, so only recognize the types that are commonly unsized.
+    fn is_ty(&self, hir_ty: &hir::Ty<'_>) -> bool {
+        match (&hir_ty.kind, self.ty.kind()) {
+            (hir::TyKind::Path(hir::QPath::Resolved(None, path)), ty::Param(param)) => {
+                match path.res {
+                    Res::Def(DefKind::TyParam, def_id) => self.tcx.item_name(def_id) == param.name,
+                    Res::SelfTy(..) => param.name == kw::SelfUpper,
+                    _ => false,
+                }
+            }
+            (hir::TyKind::Path(hir::QPath::Resolved(None, path)), ty::Adt(def, _)) => {
+                path.res.opt_def_id() == Some(def.did)
+            }
+            (hir::TyKind::Path(hir::QPath::Resolved(None, path)), ty::Str) => {
+                matches!(path.res, Res::PrimTy(hir::PrimTy::Str))
+            }
+            (hir::TyKind::Slice(_), ty::Slice(_))
+            | (hir::TyKind::TraitObject(..), ty::Dynamic(..)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'v, 'tcx> Visitor<'v> for UnsizedTupleElemVisitor<'v, 'tcx> {
+    type Map = hir::intravisit::ErasedMap<'v>;
+
+    fn nested_visit_map(&mut self) -> hir::intravisit::NestedVisitorMap<Self::Map> {
+        hir::intravisit::NestedVisitorMap::None
+    }
+
+    fn visit_ty(&mut self, hir_ty: &'v hir::Ty<'v>) {
+        if self.found.is_some() {
+            return;
+        }
+        if let hir::TyKind::Tup(elems) = hir_ty.kind {
+            if let Some((_, rest)) = elems.split_last() {
+                if self.span.contains(hir_ty.span) {
+                    if let Some(idx) = rest.iter().position(|elem| self.is_ty(elem)) {
+                        self.found = Some((elems, idx));
+                        return;
+                    }
+                }
+            }
+        }
+        hir::intravisit::walk_ty(self, hir_ty)
+    }
+}
+
 pub trait NextTypeParamName {
     fn next_type_param_name(&self, name: Option<&str>) -> String;
 }
@@ -2349,6 +6546,269 @@ fn next_type_param_name(&self, name: Option<&str>) -> String {
     }
 }
 
+/// What it takes to borrow a place mutably.
+enum MutBorrow {
+    /// The place can be borrowed mutably as it is.
+    Allowed,
+    /// The binding the place starts from has to be declared `mut` first.
+    NeedsMutBinding(Span, Ident),
+    /// The place can't be borrowed mutably, or we can't tell.
+    Impossible,
+}
+
+/// Finds the `&place` argument the obligation points at and works out whether `place` can be
+/// borrowed mutably instead. If the argument can't be found, assume that it can.
+fn mut_borrow_of_arg<'tcx>(
+    infcx: &InferCtxt<'_, 'tcx>,
+    obligation: &PredicateObligation<'tcx>,
+) -> MutBorrow {
+    let hir = infcx.tcx.hir();
+    let arg = cause_expr(hir, &obligation.cause);
+    match arg.map(|arg| &arg.kind) {
+        Some(hir::ExprKind::AddrOf(hir::BorrowKind::Ref, hir::Mutability::Not, place)) => {
+            let typeck_results = infcx.in_progress_typeck_results.map(|t| t.borrow());
+            mut_borrow_of_place(hir, typeck_results.as_deref(), place)
+        }
+        _ => MutBorrow::Allowed,
+    }
+}
+
+/// Works out whether `place` can be borrowed mutably, following field projections down to the
+/// binding they start from.
+fn mut_borrow_of_place(
+    hir: rustc_middle::hir::map::Map<'_>,
+    typeck_results: Option<&TypeckResults<'_>>,
+    mut place: &hir::Expr<'_>,
+) -> MutBorrow {
+    loop {
+        match place.kind {
+            hir::ExprKind::Field(base, _) => {
+                let typeck_results = match typeck_results {
+                    Some(typeck_results) => typeck_results,
+                    None => return MutBorrow::Impossible,
+                };
+                match typeck_results.expr_ty_opt(base).map(|ty| ty.kind()) {
+                    // A field behind a `&mut` can be reborrowed mutably even if the binding isn't
+                    // `mut`.
+                    Some(ty::Ref(_, _, hir::Mutability::Mut)) => return MutBorrow::Allowed,
+                    Some(ty::Ref(_, _, hir::Mutability::Not)) | None => {
+                        return MutBorrow::Impossible;
+                    }
+                    // Autoderef through a smart pointer, which may not implement `DerefMut`.
+                    Some(_) if !typeck_results.expr_adjustments(base).is_empty() => {
+                        return MutBorrow::Impossible;
+                    }
+                    Some(_) => place = base,
+                }
+            }
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => {
+                return match path.res {
+                    Res::Local(hir_id) => match hir.find(hir_id) {
+                        Some(Node::Binding(pat)) => match pat.kind {
+                            hir::PatKind::Binding(
+                                hir::BindingAnnotation::Unannotated,
+                                _,
+                                ident,
+                                _,
+                            ) => MutBorrow::NeedsMutBinding(pat.span, ident),
+                            hir::PatKind::Binding(hir::BindingAnnotation::Mutable, ..) => {
+                                MutBorrow::Allowed
+                            }
+                            _ => MutBorrow::Impossible,
+                        },
+                        _ => MutBorrow::Impossible,
+                    },
+                    Res::Def(DefKind::Static, _) => MutBorrow::Impossible,
+                    _ => MutBorrow::Allowed,
+                };
+            }
+            // These depend on `DerefMut` and `IndexMut` impls.
+            hir::ExprKind::Unary(hir::UnOp::UnDeref, _) | hir::ExprKind::Index(..) => {
+                return MutBorrow::Impossible;
+            }
+            // Anything else is a temporary, which can always be borrowed mutably.
+            _ => return MutBorrow::Allowed,
+        }
+    }
+}
+
+/// If the bound at `bound_span` is a supertrait of the foreign trait `trait_def_id` that has no
+/// public path from outside of its crate, returns that supertrait. Nothing outside of the crate
+/// can implement it, and so nothing can implement `trait_def_id` either.
+fn sealing_supertrait(tcx: TyCtxt<'_>, trait_def_id: DefId, bound_span: Span) -> Option<DefId> {
+    if trait_def_id.is_local() || tcx.def_kind(trait_def_id) != DefKind::Trait {
+        return None;
+    }
+    let supertrait =
+        tcx.super_predicates_of(trait_def_id).predicates.iter().find_map(|(predicate, span)| {
+            match predicate.skip_binders() {
+                ty::PredicateAtom::Trait(trait_pred, _) if *span == bound_span => {
+                    Some(trait_pred.def_id())
+                }
+                _ => None,
+            }
+        })?;
+    if supertrait.is_local() || tcx.visible_parent_map(LOCAL_CRATE).contains_key(&supertrait) {
+        return None;
+    }
+    Some(supertrait)
+}
+
+/// Note the impl that an obligation was derived from, unless the same note is already on the
+/// diagnostic. Each component of a `dyn A + B + C` can lead through the same impls, and repeating
+/// the requirement chain for every one of them only adds noise.
+fn note_impl_requirements(
+    err: &mut DiagnosticBuilder<'_>,
+    trait_ref: ty::PolyTraitRef<'_>,
+    tree_depth: Option<usize>,
+) {
+    let msg = cause_tree_step(tree_depth, impl_requirements_msg(trait_ref));
+    let already_noted = err.children.iter().any(|child| {
+        child.level == Level::Note
+            && child.span.primary_spans().is_empty()
+            && child.message() == msg
+    });
+    if !already_noted {
+        err.note(&msg);
+    }
+}
+
+fn impl_requirements_msg(trait_ref: ty::PolyTraitRef<'_>) -> String {
+    format!(
+        "required because of the requirements on the impl of `{}` for `{}`",
+        trait_ref.print_only_trait_path(),
+        trait_ref.skip_binder().self_ty()
+    )
+}
+
+/// Numbers a step of a chain of requirements and indents it under the previous one, when they are
+/// listed as a tree with `-Z trait-cause-tree`.
+fn cause_tree_step(tree_depth: Option<usize>, msg: String) -> String {
+    match tree_depth {
+        Some(depth) => format!("{}{}. {}", "  ".repeat(depth), depth + 1, msg),
+        None => msg,
+    }
+}
+
+/// Numbers the requirement a chain of requirements starts from, which is a label and so isn't
+/// indented, when they are listed as a tree with `-Z trait-cause-tree`.
+fn cause_tree_root(tree_depth: Option<usize>, msg: String) -> String {
+    match tree_depth {
+        Some(depth) => format!("{}. {}", depth + 1, msg),
+        None => msg,
+    }
+}
+
+/// Where the function whose signature a suggestion would change is declared.
+#[derive(Clone, Copy, PartialEq)]
+enum FnSigOwner {
+    /// A free function or an inherent method, which can use `impl Trait` in its signature.
+    Free,
+    /// A method declared in a trait, which can't use `impl Trait` in its signature.
+    Trait,
+    /// A method of a trait impl, whose signature has to keep matching the trait's.
+    TraitImpl,
+    /// A closure, which can't use `impl Trait` in its signature.
+    Closure,
+}
+
+/// The declaration and body of the function `hir_id` is, or is in, and where it is declared.
+fn enclosing_fn_sig<'hir>(
+    tcx: TyCtxt<'hir>,
+    hir_id: hir::HirId,
+) -> Option<(&'hir hir::FnDecl<'hir>, &'hir hir::Body<'hir>, FnSigOwner)> {
+    let hir = tcx.hir();
+    let owner = if hir.maybe_body_owned_by(hir_id).is_some() {
+        hir_id
+    } else {
+        hir.parent_iter(hir_id).find(|&(id, _)| hir.maybe_body_owned_by(id).is_some())?.0
+    };
+    let sig_owner = match hir.get(owner) {
+        Node::ImplItem(_) => match hir.expect_item(hir.get_parent_item(owner)).kind {
+            hir::ItemKind::Impl { of_trait: Some(_), .. } => FnSigOwner::TraitImpl,
+            _ => FnSigOwner::Free,
+        },
+        Node::TraitItem(_) => FnSigOwner::Trait,
+        Node::Item(_) => FnSigOwner::Free,
+        _ => FnSigOwner::Closure,
+    };
+    Some((hir.fn_decl_by_hir_id(owner)?, hir.body(hir.maybe_body_owned_by(owner)?), sig_owner))
+}
+
+/// If `ty` is written as a trait object, returns it without the `dyn`, like `Trait + Send`, along
+/// with whether its trait is object safe.
+fn written_trait_object(tcx: TyCtxt<'_>, ty: &hir::Ty<'_>) -> Option<(String, bool)> {
+    let is_object_safe = match ty.kind {
+        hir::TyKind::TraitObject([principal, ..], _) => principal
+            .trait_ref
+            .trait_def_id()
+            .map_or(true, |def_id| tcx.object_safety_violations(def_id).is_empty()),
+        _ => return None,
+    };
+    let snippet = tcx.sess.source_map().span_to_snippet(ty.span).ok()?;
+    let has_dyn = snippet.split_whitespace().next().map_or(false, |s| s == "dyn");
+    let trait_obj = if has_dyn { snippet[4..].trim_start() } else { &snippet[..] };
+    Some((trait_obj.to_string(), is_object_safe))
+}
+
+/// When a function argument is declared as a trait object, suggest borrowing it, or boxing it if
+/// it's a `dyn FnOnce`, which can't be called through a reference, or accepting any type that
+/// implements the trait if `impl Trait` can be used there. Returns whether the argument is a trait
+/// object, in which case no other change to its type should be suggested.
+fn suggest_borrowing_trait_object_param(
+    tcx: TyCtxt<'_>,
+    err: &mut DiagnosticBuilder<'_>,
+    ty: &hir::Ty<'_>,
+    sig_owner: FnSigOwner,
+) -> bool {
+    let trait_obj = match written_trait_object(tcx, ty) {
+        Some((trait_obj, _)) => trait_obj,
+        None => return false,
+    };
+    // Changing the argument's type would make the signature differ from the trait's.
+    if sig_owner == FnSigOwner::TraitImpl {
+        return true;
+    }
+    let is_fn_once = match ty.kind {
+        hir::TyKind::TraitObject([principal, ..], _) => {
+            principal.trait_ref.trait_def_id() == tcx.lang_items().fn_once_trait()
+        }
+        _ => false,
+    };
+    if is_fn_once {
+        err.span_suggestion_verbose(
+            ty.span,
+            "function arguments must have a statically known size, and a `FnOnce` closure can \
+             only be called when it's owned, so consider boxing it",
+            format!("Box<dyn {}>", trait_obj),
+            Applicability::MaybeIncorrect,
+        );
+    } else {
+        // `&dyn A + B` would be ambiguous.
+        let borrowed = if trait_obj.contains('+') {
+            format!("&(dyn {})", trait_obj)
+        } else {
+            format!("&dyn {}", trait_obj)
+        };
+        err.span_suggestion_verbose(
+            ty.span,
+            "function arguments must have a statically known size, borrowed types always have a \
+             known size",
+            borrowed,
+            Applicability::MachineApplicable,
+        );
+    }
+    if sig_owner == FnSigOwner::Free {
+        err.span_suggestion_verbose(
+            ty.span,
+            &format!("alternatively, accept any type that implements `{}`", trait_obj),
+            format!("impl {}", trait_obj),
+            Applicability::MaybeIncorrect,
+        );
+    }
+    true
+}
+
 fn suggest_trait_object_return_type_alternatives(
     err: &mut DiagnosticBuilder<'_>,
     ret_ty: Span,
@@ -2384,3 +6844,61 @@ fn suggest_trait_object_return_type_alternatives(
         );
     }
 }
+
+/// A recursive `async fn` returns a future that contains itself, which has an infinite size.
+/// Suggest breaking the cycle by boxing the future, which also has to be pinned so that it can
+/// be awaited: `async fn foo() -> T { .. }` →
+/// `fn foo() -> std::pin::Pin<Box<dyn std::future::Future<Output = T>>> {
+/// Box::pin(async move { .. }) }`. The paths are spelled out as `Pin` and `Future` usually aren't
+/// imported.
+///
+/// Only `async fn`s, including async methods, are handled. Async blocks and closures can't name
+/// themselves, so they only recurse through a function returning them, and the cycle is then
+/// reported on that function's `impl Trait` return type rather than through E0733.
+pub fn suggest_boxing_for_recursion(
+    tcx: TyCtxt<'_>,
+    err: &mut DiagnosticBuilder<'_>,
+    fn_hir_id: hir::HirId,
+) {
+    let hir = tcx.hir();
+    let source_map = tcx.sess.source_map();
+    let sig = match hir.fn_sig_by_hir_id(fn_hir_id) {
+        Some(sig) if sig.header.asyncness == hir::IsAsync::Async => sig,
+        _ => return,
+    };
+    let body_id = match hir.find(fn_hir_id).and_then(|node| node.body_id()) {
+        Some(body_id) => body_id,
+        None => return,
+    };
+    let body_span = hir.body(body_id).value.span;
+    // The return type of an `async fn` is lowered to `impl Future<Output = T>`, but its span is
+    // still the one of the written `T`, or an empty one when it was left out.
+    let ret_span = sig.decl.output.span();
+    if sig.span.from_expansion() || body_span.from_expansion() || ret_span.from_expansion() {
+        return;
+    }
+    let (sig_snippet, ret_snippet) =
+        match (source_map.span_to_snippet(sig.span), source_map.span_to_snippet(ret_span)) {
+            (Ok(sig_snippet), Ok(ret_snippet)) => (sig_snippet, ret_snippet),
+            _ => return,
+        };
+    let async_len = match sig_snippet.strip_prefix("async") {
+        Some(rest) => sig_snippet.len() - rest.trim_start().len(),
+        None => return,
+    };
+    let ret_sugg = if ret_snippet.is_empty() {
+        "-> std::pin::Pin<Box<dyn std::future::Future<Output = ()>>> ".to_string()
+    } else {
+        format!("std::pin::Pin<Box<dyn std::future::Future<Output = {}>>>", ret_snippet)
+    };
+    err.multipart_suggestion(
+        "to allow the recursion, return a pinned and boxed future instead",
+        vec![
+            (sig.span.with_hi(sig.span.lo() + BytePos(async_len as u32)), String::new()),
+            (ret_span, ret_sugg),
+            (body_span.shrink_to_lo(), "{ Box::pin(async move ".to_string()),
+            (body_span.shrink_to_hi(), ") }".to_string()),
+        ],
+        Applicability::MaybeIncorrect,
+    );
+}
@@ -21,6 +21,7 @@
 use rustc_span::{self, MultiSpan, Span};
 use rustc_target::spec::abi::Abi;
 use rustc_trait_selection::opaque_types::InferCtxtExt as _;
+use rustc_trait_selection::traits::error_reporting::suggestions::suggest_boxing_for_recursion;
 use rustc_trait_selection::traits::error_reporting::InferCtxtExt as _;
 use rustc_trait_selection::traits::{self, ObligationCauseCode};
 
@@ -588,7 +589,7 @@ pub(super) fn check_opaque_for_cycles<'tcx>(
     if let Err(partially_expanded_type) = tcx.try_expand_impl_trait_type(def_id.to_def_id(), substs)
     {
         match origin {
-            hir::OpaqueTyOrigin::AsyncFn => async_opaque_type_cycle_error(tcx, span),
+            hir::OpaqueTyOrigin::AsyncFn => async_opaque_type_cycle_error(tcx, def_id, span),
             hir::OpaqueTyOrigin::Binding => {
                 binding_opaque_type_cycle_error(tcx, def_id, span, partially_expanded_type)
             }
@@ -1436,11 +1437,14 @@ pub(super) fn check_impl_item_well_formed(tcx: TyCtxt<'_>, def_id: LocalDefId) {
     wfcheck::check_impl_item(tcx, def_id);
 }
 
-fn async_opaque_type_cycle_error(tcx: TyCtxt<'tcx>, span: Span) {
-    struct_span_err!(tcx.sess, span, E0733, "recursion in an `async fn` requires boxing")
-        .span_label(span, "recursive `async fn`")
-        .note("a recursive `async fn` must be rewritten to return a boxed `dyn Future`")
-        .emit();
+fn async_opaque_type_cycle_error(tcx: TyCtxt<'tcx>, def_id: LocalDefId, span: Span) {
+    let mut err =
+        struct_span_err!(tcx.sess, span, E0733, "recursion in an `async fn` requires boxing");
+    err.span_label(span, "recursive `async fn`");
+    err.note("a recursive `async fn` must be rewritten to return a boxed `dyn Future`");
+    let fn_hir_id = tcx.hir().get_parent_item(tcx.hir().local_def_id_to_hir_id(def_id));
+    suggest_boxing_for_recursion(tcx, &mut err, fn_hir_id);
+    err.emit();
 }
 
 /// Emit an error for recursive opaque types.
@@ -437,7 +437,7 @@ fn sig_of_closure_with_mismatched_number_of_arguments(
             .collect();
         let (closure_span, found_args) = match self.get_fn_like_arguments(expr_map_node) {
             Some((sp, args)) => (Some(sp), args),
-            None => (None, Vec::new()),
+            None => (None, vec![ArgKind::empty(); decl.inputs.len()]),
         };
         let expected_span =
             expected_sig.cause_span.unwrap_or_else(|| hir.span_if_local(expr_def_id).unwrap());
@@ -447,6 +447,7 @@ fn sig_of_closure_with_mismatched_number_of_arguments(
             expected_args,
             found_args,
             true,
+            Some(body.id()),
         )
         .emit();
 
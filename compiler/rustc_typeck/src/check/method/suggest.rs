@@ -444,6 +444,47 @@ pub fn report_method_error<'b>(
                             ),
                         );
                     }
+
+                    // The item might only be defined for some other instantiation of the type,
+                    // like calling `Foo::<i32>::bar` when there's only an `impl Foo<u32>`.
+                    if unsatisfied_predicates.is_empty() {
+                        let other_impls = tcx
+                            .inherent_impls(def.did)
+                            .iter()
+                            .filter(|&&impl_did| {
+                                tcx.associated_items(impl_did)
+                                    .filter_by_name_unhygienic(item_name.name)
+                                    .any(|item| item.kind == ty::AssocKind::Fn)
+                            })
+                            .map(|&impl_did| (impl_did, tcx.at(span).type_of(impl_did)))
+                            .filter(|(_, impl_ty)| *impl_ty != actual.peel_refs())
+                            .collect::<Vec<_>>();
+                        match &other_impls[..] {
+                            [] => {}
+                            [(impl_did, impl_ty)] => {
+                                err.span_note(
+                                    tcx.sess.source_map().guess_head_span(tcx.def_span(*impl_did)),
+                                    &format!(
+                                        "the {} `{}` is only available for `{}`, not for `{}`",
+                                        item_kind, item_name, impl_ty, ty_str,
+                                    ),
+                                );
+                            }
+                            _ => {
+                                err.note(&format!(
+                                    "the {} `{}` is only available for the following \
+                                     instantiations of `{}`:{}",
+                                    item_kind,
+                                    item_name,
+                                    tcx.def_path_str(def.did),
+                                    other_impls
+                                        .iter()
+                                        .map(|(_, impl_ty)| format!("\n  `{}`", impl_ty))
+                                        .collect::<String>(),
+                                ));
+                            }
+                        }
+                    }
                 }
 
                 // If the method name is the name of a field with a function or closure type,
@@ -1273,21 +1273,28 @@ fn check_variances_for_type_defn<'tcx>(
 
         match param.name {
             hir::ParamName::Error => {}
-            _ => report_bivariance(tcx, param.span, param.name.ident().name),
+            _ => report_bivariance(tcx, param),
         }
     }
 }
 
-fn report_bivariance(tcx: TyCtxt<'_>, span: Span, param_name: Symbol) {
-    let mut err = error_392(tcx, span, param_name);
+fn report_bivariance(tcx: TyCtxt<'_>, param: &hir::GenericParam<'_>) {
+    let param_name = param.name.ident().name;
+    let mut err = error_392(tcx, param.span, param_name);
 
     let suggested_marker_id = tcx.lang_items().phantom_data();
     // Help is available only in presence of lang items.
     let msg = if let Some(def_id) = suggested_marker_id {
+        // A lifetime can't be used as the marker's parameter directly, show how to use it.
+        let marker = match param.kind {
+            hir::GenericParamKind::Lifetime { .. } => {
+                format!("{}<&{} ()>", tcx.def_path_str(def_id), param_name)
+            }
+            _ => tcx.def_path_str(def_id),
+        };
         format!(
             "consider removing `{}`, referring to it in a field, or using a marker such as `{}`",
-            param_name,
-            tcx.def_path_str(def_id),
+            param_name, marker,
         )
     } else {
         format!("consider removing `{}` or referring to it in a field", param_name)
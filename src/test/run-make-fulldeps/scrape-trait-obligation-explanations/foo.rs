@@ -0,0 +1,126 @@
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_infer;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_span;
+extern crate rustc_trait_selection;
+
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_interface::interface;
+use rustc_middle::ty::{self, ToPredicate, TyCtxt, WithConstness};
+use rustc_session::config::{CrateType, Input, Options};
+use rustc_session::DiagnosticOutput;
+use rustc_span::source_map::FileName;
+use rustc_trait_selection::traits::error_reporting::InferCtxtExt;
+use rustc_trait_selection::traits::{
+    Obligation, ObligationCause, TraitExplanation, TraitExplanationKind,
+};
+
+use std::path::PathBuf;
+
+fn main() {
+    let src = r#"
+    pub struct Point {
+        x: i32,
+    }
+
+    pub struct NotClone;
+
+    pub struct Holder {
+        inner: NotClone,
+    }
+    "#;
+
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 4 {
+        panic!("expected rustc path");
+    }
+
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut opts = Options::default();
+    opts.crate_types = vec![CrateType::Rlib];
+    opts.maybe_sysroot = Some(sysroot);
+
+    let name = FileName::anon_source_code(src);
+    let input = Input::Str { name, input: src.to_string() };
+
+    let config = interface::Config {
+        opts,
+        crate_cfg: Default::default(),
+        input,
+        input_path: None,
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        diagnostic_output: DiagnosticOutput::Default,
+        stderr: None,
+        lint_caps: Default::default(),
+        register_lints: None,
+        override_queries: None,
+        make_codegen_backend: None,
+        registry: rustc_driver::diagnostics_registry(),
+    };
+
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| {
+            queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+                // A local type that can derive `Clone` gets a suggestion to do so.
+                let point = explain_clone(tcx, "Point");
+                assert_eq!(point[0].kind, TraitExplanationKind::Message);
+                assert_eq!(point[0].message, "the trait bound `Point: Clone` is not satisfied");
+                let derive = point
+                    .iter()
+                    .find(|explanation| explanation.kind == TraitExplanationKind::Suggestion)
+                    .expect("expected a suggestion for `Point: Clone`");
+                assert!(derive.message.contains("#[derive(Clone)]"), "{:?}", derive);
+                assert!(
+                    derive.edits.iter().any(|(_, snippet)| snippet.contains("#[derive(Clone)]"))
+                );
+
+                // A local type with a field that isn't `Clone` only gets a note explaining why.
+                let holder = explain_clone(tcx, "Holder");
+                assert!(
+                    holder
+                        .iter()
+                        .all(|explanation| explanation.kind != TraitExplanationKind::Suggestion),
+                    "{:?}",
+                    holder,
+                );
+                assert!(holder.iter().any(|explanation| {
+                    explanation.kind == TraitExplanationKind::Note
+                        && explanation.message.contains("can't derive `Clone`")
+                }));
+
+                // Explaining an obligation doesn't report anything.
+                assert_eq!(tcx.sess.err_count(), 0);
+            });
+        });
+    });
+}
+
+fn explain_clone<'tcx>(tcx: TyCtxt<'tcx>, type_name: &str) -> Vec<TraitExplanation<'tcx>> {
+    let hir_id = tcx
+        .hir()
+        .krate()
+        .items
+        .values()
+        .find(|item| item.ident.as_str() == type_name)
+        .map(|item| item.hir_id)
+        .unwrap();
+    let ty = tcx.type_of(tcx.hir().local_def_id(hir_id));
+    let clone_trait = tcx.lang_items().clone_trait().unwrap();
+    let trait_ref = ty::TraitRef::new(clone_trait, tcx.mk_substs_trait(ty, &[]));
+    let obligation = Obligation::new(
+        ObligationCause::dummy(),
+        ty::ParamEnv::empty(),
+        trait_ref.without_const().to_predicate(tcx),
+    );
+    tcx.infer_ctxt().enter(|infcx| infcx.scrape_trait_obligation_explanations(&obligation, false))
+}
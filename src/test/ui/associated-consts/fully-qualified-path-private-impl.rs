@@ -0,0 +1,42 @@
+// Check that we don't suggest a fully qualified path through a type that isn't accessible, but
+// do suggest one through a type that is.
+
+mod shapes {
+    pub trait Shape {
+        const SIDES: usize;
+    }
+
+    struct Hidden;
+
+    impl Shape for Hidden {
+        const SIDES: usize = 3;
+    }
+}
+
+mod colors {
+    pub trait Color {
+        const RGB: (u8, u8, u8);
+    }
+
+    struct Secret;
+
+    impl Color for Box<Secret> {
+        const RGB: (u8, u8, u8) = (0, 0, 0);
+    }
+
+    pub struct Red;
+
+    impl Color for Red {
+        const RGB: (u8, u8, u8) = (255, 0, 0);
+    }
+}
+
+use colors::Color;
+use shapes::Shape;
+
+fn main() {
+    let _ = Shape::SIDES;
+    //~^ ERROR type annotations needed
+    let _ = Color::RGB;
+    //~^ ERROR type annotations needed
+}
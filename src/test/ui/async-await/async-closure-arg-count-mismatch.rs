@@ -0,0 +1,14 @@
+// edition:2018
+// An async closure that takes the wrong number of arguments is reported like any other closure,
+// pointing at the arguments it takes rather than at the generator its body is lowered into.
+
+#![feature(async_closure)]
+
+fn call<F: Fn(u32) -> R, R>(_: F) {}
+
+fn main() {
+    call(async || {});
+    //~^ ERROR closure is expected to take 1 argument, but it takes 0 arguments
+    call(async |_a, _b| {});
+    //~^ ERROR closure is expected to take 1 argument, but it takes 2 arguments
+}
@@ -0,0 +1,20 @@
+// edition:2018
+
+// Check that argument count mismatches involving an `async fn` point at the signature the user
+// wrote rather than at the desugared body.
+
+async fn one(_: u32) {}
+
+async fn two(_: u32, _: u32) {}
+
+fn call<F: Fn(u32)>(_: F) {}
+
+fn call_once<F: FnOnce(u32)>(_: F) {}
+
+fn main() {
+    call(two);
+    //~^ ERROR function is expected to take 1 argument, but it takes 2 arguments
+    call_once(two);
+    //~^ ERROR function is expected to take 1 argument, but it takes 2 arguments
+    call(one);
+}
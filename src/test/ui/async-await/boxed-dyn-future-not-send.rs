@@ -0,0 +1,57 @@
+// edition:2018
+
+// Check that when a future holds a boxed trait-object future that is not `Send` across an await,
+// adding `+ Send` to the trait object is suggested, but only if the boxed future is known
+// to be `Send` without looking into the type of an `async fn`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+fn boxed() -> Pin<Box<dyn Future<Output = ()>>> {
+    Box::pin(async {})
+}
+
+fn boxed_rc() -> Pin<Box<dyn Future<Output = ()>>> {
+    let rc = Rc::new(());
+    Box::pin(async move { drop(rc) })
+}
+
+async fn ready() {}
+
+fn boxed_opaque() -> Pin<Box<dyn Future<Output = ()>>> {
+    Box::pin(ready())
+}
+
+fn returned() {
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        boxed().await;
+    });
+}
+
+fn annotated() {
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        let fut: Pin<Box<dyn Future<Output = ()>>> = Box::pin(async {});
+        fut.await;
+    });
+}
+
+fn not_send() {
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        boxed_rc().await;
+    });
+}
+
+fn opaque() {
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        boxed_opaque().await;
+    });
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// edition:2018
+#![feature(negative_impls)]
+
+// Check that we explain that a value with a `Drop` impl lives until the end of its scope when it
+// makes a future not `Send`, and suggest moving it into an inner block unless it's used later.
+
+struct Guard;
+
+impl !Send for Guard {}
+
+impl Drop for Guard {
+    fn drop(&mut self) {}
+}
+
+fn is_send<T: Send>(t: T) { }
+
+async fn bar() {}
+
+async fn foo() {
+    let guard = Guard;
+    bar().await;
+}
+
+async fn used_later() {
+    let guard = Guard;
+    bar().await;
+    drop(guard);
+}
+
+fn main() {
+    is_send(foo());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(used_later());
+    //~^ ERROR future cannot be sent between threads safely
+}
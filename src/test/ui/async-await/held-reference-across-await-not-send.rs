@@ -0,0 +1,24 @@
+#![feature(negative_impls)]
+// edition:2018
+
+// Check that the async-await specific note points at a reference held across an await when the
+// referenced type is the one that doesn't implement the auto trait.
+
+struct Foo;
+
+impl !Sync for Foo {}
+
+fn is_send<T: Send>(t: T) { }
+
+async fn bar(foo: &Foo) {
+    let x = foo;
+    baz().await;
+}
+
+async fn baz() { }
+
+fn main() {
+    let foo = Foo;
+    is_send(bar(&foo));
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,32 @@
+// edition:2018
+
+// Check that a non-`Send` value moved into an async block is pointed at where it is moved in and
+// reported as held by the future, while one captured by reference keeps the general note.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn fut() {}
+
+fn moved() {
+    let rc = Rc::new(0);
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        let _ = *rc + 1;
+        fut().await;
+        drop(rc);
+    });
+}
+
+fn borrowed() {
+    let counter = Cell::new(0);
+    is_send(async {
+        //~^ ERROR future cannot be sent between threads safely
+        counter.set(1);
+        fut().await;
+    });
+}
+
+fn main() {}
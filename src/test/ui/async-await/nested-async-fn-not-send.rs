@@ -0,0 +1,27 @@
+// edition:2018
+// When a value that isn't `Send` is held across an await in an `async fn` that's only awaited by
+// other `async fn`s, point at the value as well as at each await that leads to it.
+
+use std::rc::Rc;
+
+fn require_send(_: impl Send) {}
+
+async fn ready() {}
+
+async fn inner() {
+    let rc = Rc::new(());
+    ready().await;
+}
+
+async fn middle() {
+    inner().await;
+}
+
+async fn outer() {
+    middle().await;
+}
+
+fn main() {
+    require_send(outer());
+    //~^ ERROR future cannot be sent between threads safely
+}
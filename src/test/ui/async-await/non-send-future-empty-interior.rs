@@ -0,0 +1,16 @@
+// edition:2018
+
+// Check that a future whose generator interior holds no types, as there is no await in its body,
+// is still explained through the value it captures.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+fn main() {
+    let rc = Rc::new(0);
+    is_send(async move {
+        //~^ ERROR future cannot be sent between threads safely
+        drop(rc);
+    });
+}
@@ -0,0 +1,24 @@
+// edition:2018
+
+// Check that every error about the same non-`Send` future points at the value held across the
+// await, not just the first one.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn holds_rc() {
+    let rc = Rc::new(());
+    ready().await;
+}
+
+async fn ready() {}
+
+fn main() {
+    is_send(holds_rc());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(holds_rc());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(holds_rc());
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,8 @@
+// edition:2018
+
+async fn count_down(n: u32) -> u32 {
+    //~^ ERROR recursion in an `async fn` requires boxing
+    if n == 0 { 0 } else { count_down(n - 1).await }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+// edition:2018
+
+// Check that we suggest a `move` async block when a value captured by reference makes the future
+// non-`Send`, but only when capturing the value itself would make it `Send`.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+fn cell() {
+    let counter = Cell::new(0);
+    is_send(async { counter.set(counter.get() + 1) });
+    //~^ ERROR future cannot be sent between threads safely
+}
+
+fn rc() {
+    let counter = Rc::new(0);
+    is_send(async { *counter + 1 });
+    //~^ ERROR future cannot be sent between threads safely
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+// edition:2018
+// Check that we suggest pinning a future that isn't `Unpin` with `Box::pin`, but not one that is,
+// as passing that by value is enough.
+
+use std::future::Future;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+struct Ticker;
+
+impl Future for Ticker {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+struct SelfRef {
+    _pinned: PhantomPinned,
+}
+
+impl Future for SelfRef {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+fn drive<F: Future>(_: F) {}
+
+fn main() {
+    let ticker = Ticker;
+    drive(&ticker);
+    //~^ ERROR `&Ticker` is not a future
+    let mut self_ref = SelfRef { _pinned: PhantomPinned };
+    drive(&mut self_ref);
+    //~^ ERROR `PhantomPinned` cannot be unpinned
+}
@@ -0,0 +1,40 @@
+// edition:2018
+
+// Check that we suggest the thread-safe counterparts of the standard types that commonly make a
+// future not `Send`.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn yield_now() {}
+
+async fn rc() {
+    let value = Rc::new(0);
+    yield_now().await;
+    drop(value);
+}
+
+async fn ref_cell() {
+    let value = RefCell::new(0);
+    let value_ref = &value;
+    yield_now().await;
+    *value_ref.borrow_mut() += 1;
+}
+
+async fn cell() {
+    let value = Cell::new(0);
+    let value_ref = &value;
+    yield_now().await;
+    value_ref.set(1);
+}
+
+fn main() {
+    is_send(rc());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(ref_cell());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(cell());
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,27 @@
+// run-rustfix
+// A reference to a local can't be returned, but the local itself, or a copy of it, can.
+
+#[derive(Clone)]
+struct Config {
+    verbose: bool,
+}
+
+fn greeting(name: &str) -> &str {
+    let greeting = format!("hello, {}", name);
+    &greeting
+    //~^ ERROR cannot return reference to local variable `greeting`
+}
+
+fn config(cached: &Config, reload: bool) -> &Config {
+    if reload {
+        let fresh = Config { verbose: false };
+        return &fresh;
+        //~^ ERROR cannot return reference to local variable `fresh`
+    }
+    cached
+}
+
+fn main() {
+    greeting("ferris");
+    config(&Config { verbose: true }, false);
+}
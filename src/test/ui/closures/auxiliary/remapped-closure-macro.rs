@@ -0,0 +1,8 @@
+// compile-flags: --remap-path-prefix={{src-base}}=/the/aux-src
+
+#[macro_export]
+macro_rules! adder {
+    () => {
+        |a, b| a + b
+    };
+}
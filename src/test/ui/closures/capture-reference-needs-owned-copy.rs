@@ -0,0 +1,19 @@
+use std::thread;
+
+fn main() {
+    nested();
+
+    let greeting = String::from("hello");
+    let name = &greeting;
+    //~^ ERROR `greeting` does not live long enough
+    thread::spawn(|| println!("{}", name));
+    //~^ ERROR closure may outlive the current function, but it borrows `name`
+}
+
+fn nested() {
+    let greeting = "hello";
+    let name = &greeting;
+    //~^ ERROR `greeting` does not live long enough
+    thread::spawn(|| println!("{}", name));
+    //~^ ERROR closure may outlive the current function, but it borrows `name`
+}
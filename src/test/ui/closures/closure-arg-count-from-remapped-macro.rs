@@ -0,0 +1,11 @@
+// Check that the arguments of a closure are still counted when its source isn't available.
+
+// aux-build:remapped-closure-macro.rs
+
+#[macro_use]
+extern crate remapped_closure_macro;
+
+fn main() {
+    let _ = [1, 2].iter().map(adder!());
+    //~^ ERROR closure is expected to take 1 argument, but it takes 2 arguments
+}
@@ -0,0 +1,13 @@
+// Check that when a closure defined apart from the call that needs it has to accept arguments with
+// any lifetime, we explain why and suggest annotating its parameter with the expected type.
+
+fn for_each_word<F: Fn(&str)>(text: &str, f: F) {
+    text.split(' ').for_each(|word| f(word));
+}
+
+fn main() {
+    let shout = |word| println!("{}!", word);
+    shout("hey");
+    for_each_word("hello world", shout);
+    //~^ ERROR type mismatch in closure arguments
+}
@@ -0,0 +1,12 @@
+// Check that we explain a closure argument mismatch where the closure's signature was inferred
+// with a specific lifetime, while the bound requires it to accept any lifetime.
+
+fn apply<F: Fn(&mut Vec<u32>)>(f: F) {
+    f(&mut vec![]);
+}
+
+fn main() {
+    let push = |v| Vec::push(v, 1);
+    apply(push);
+    //~^ ERROR type mismatch in closure arguments
+}
@@ -0,0 +1,22 @@
+// Check that we suggest annotating closure parameters whose inferred types don't match the
+// signature required by a bound.
+
+fn call<F: Fn(&u32)>(f: F) {
+    f(&0);
+}
+
+fn call_two<F: Fn(&u32, &u32)>(f: F) {
+    f(&0, &1);
+}
+
+fn main() {
+    let print = |x| println!("{}", x);
+    print(&1u32);
+    call(print);
+    //~^ ERROR type mismatch in closure arguments
+
+    let add = |x, y| println!("{}", x + y);
+    add(&1u32, &2u32);
+    call_two(add);
+    //~^ ERROR type mismatch in closure arguments
+}
@@ -0,0 +1,17 @@
+// Check that we suggest adding a `where` clause bound that only the impl method has to the trait
+// method, or splitting it off of the impl method's `where` clause.
+
+use std::fmt::Debug;
+
+trait Store {
+    fn put<T>(&mut self, value: T) where T: Debug;
+}
+
+struct Buffer;
+
+impl Store for Buffer {
+    fn put<T>(&mut self, _value: T) where T: Debug + Clone {}
+    //~^ ERROR impl has stricter requirements than trait
+}
+
+fn main() {}
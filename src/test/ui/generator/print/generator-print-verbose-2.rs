@@ -17,7 +17,7 @@ fn assert_send<T: Send>(_: T) {}
 
     let a = Cell::new(2);
     assert_send(|| {
-        //~^ ERROR: E0277
+        //~^ ERROR: generator cannot be sent between threads safely
         drop(&a);
         yield;
     });
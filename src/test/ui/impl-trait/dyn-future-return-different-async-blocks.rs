@@ -0,0 +1,14 @@
+// edition:2018
+// Returning different futures from a `-> dyn Future` function suggests pinning and boxing them.
+
+use std::future::Future;
+
+fn make(fast: bool) -> dyn Future<Output = u32> { //~ ERROR E0746
+    if fast {
+        return async { 1 };
+    } else {
+        return async { 2 };
+    }
+}
+
+fn main() {}
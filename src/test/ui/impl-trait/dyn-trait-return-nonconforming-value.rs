@@ -0,0 +1,13 @@
+trait Trait {}
+struct Struct;
+impl Trait for Struct {}
+
+fn foo(b: bool) -> dyn Trait {
+    //~^ ERROR the size for values of type `(dyn Trait + 'static)` cannot be known
+    if b {
+        return Struct;
+    }
+    "hello"
+}
+
+fn main() {}
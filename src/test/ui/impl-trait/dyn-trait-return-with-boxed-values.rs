@@ -0,0 +1,20 @@
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+fn square(side: f64) -> dyn Shape { //~ ERROR E0746
+    if side < 0.0 {
+        return Box::new(Square(0.0));
+    }
+    Box::new(Square(side))
+}
+
+fn main() {}
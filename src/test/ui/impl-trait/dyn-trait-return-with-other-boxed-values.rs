@@ -0,0 +1,28 @@
+// Only values boxed with `Box::new` are suggested to be returned without their box: the argument
+// of any other function returning a `Box` isn't necessarily the boxed value.
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+struct Factory;
+
+impl Factory {
+    fn new(side: f64) -> Box<Square> {
+        Box::new(Square(side))
+    }
+}
+
+fn square(side: f64) -> dyn Shape { //~ ERROR E0277
+    Factory::new(side)
+}
+
+fn main() {}
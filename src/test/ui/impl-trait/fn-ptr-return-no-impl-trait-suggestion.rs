@@ -0,0 +1,11 @@
+// An unsized return type that isn't `dyn Trait` isn't the case `impl Trait` is suggested for, even
+// when the values in it can be called like a `dyn Fn` can.
+
+#![allow(dead_code)]
+
+fn pick() -> [fn(u8) -> u8] {
+    //~^ ERROR the size for values of type `[fn(u8) -> u8]` cannot be known at compilation time
+    loop {}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Check that we point at the impl when a method is only defined for a different instantiation of
+// the receiver's type.
+
+struct Foo<T>(T);
+
+impl Foo<u32> {
+    fn bar(&self) {}
+}
+
+fn main() {
+    let foo = Foo(1i32);
+    foo.bar();
+    //~^ ERROR no method named `bar` found for struct `Foo<i32>` in the current scope
+}
@@ -0,0 +1,8 @@
+// Check that the closure body isn't rewritten to use argument names that are already taken.
+
+fn main() {
+    let mut v = vec![3u8, 1, 2];
+    let pair_1 = &0u8;
+    v.sort_by(|pair| pair.0.cmp(pair_1));
+    //~^ ERROR closure is expected to take 2 arguments, but it takes 1 argument
+}
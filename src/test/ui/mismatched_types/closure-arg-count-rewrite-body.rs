@@ -0,0 +1,14 @@
+// run-rustfix
+// Check that the suggestions to switch between a tuple and individual closure arguments keep the
+// closure body working.
+
+fn main() {
+    let mut v = vec![3u8, 1, 2];
+    v.sort_by(|pair| pair.0.cmp(pair.1));
+    //~^ ERROR closure is expected to take 2 arguments, but it takes 1 argument
+    let _it = v.iter().enumerate().map(|i, x| i + *x as usize);
+    //~^ ERROR closure is expected to take a single 2-tuple as argument
+    let w = vec![(1usize, 2usize)];
+    let _it = w.into_iter().enumerate().map(|i, (a, b)| i + a + b);
+    //~^ ERROR closure is expected to take a single 2-tuple as argument
+}
@@ -0,0 +1,18 @@
+fn call<F: Fn(u8) -> u32>(f: F) -> u32 {
+    f(0)
+}
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn len<T>(v: Vec<T>) -> u32 {
+    v.len() as u32
+}
+
+fn main() {
+    let f: fn(u32) -> u32 = double;
+    call(f); //~ ERROR type mismatch in function arguments
+    call(double); //~ ERROR type mismatch in function arguments
+    call(len); //~ ERROR type mismatch in function arguments
+}
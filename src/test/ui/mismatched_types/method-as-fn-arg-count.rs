@@ -0,0 +1,15 @@
+// Check that we point out the `self` receiver when a method is passed where a function taking
+// fewer arguments is expected.
+
+struct Foo;
+
+impl Foo {
+    fn bar(&self, _: u32) {}
+}
+
+fn call<F: Fn(u32)>(_: F) {}
+
+fn main() {
+    call(Foo::bar);
+    //~^ ERROR function is expected to take 1 argument, but it takes 2 arguments
+}
@@ -0,0 +1,20 @@
+// Check that a custom `#[rustc_on_unimplemented]` message isn't followed by generic notes about
+// other mutabilities, while suggestions that are known to apply are still given.
+
+#![feature(rustc_attrs)]
+
+#[rustc_on_unimplemented(message = "`{Self}` cannot be cached", label = "not cacheable")]
+trait Cacheable {}
+
+impl Cacheable for &mut u8 {}
+
+fn store<T: Cacheable>(_: T) {}
+
+fn main() {
+    let x = 0u8;
+    let r = &x;
+    store(r);
+    //~^ ERROR `&u8` cannot be cached
+    store(&x);
+    //~^ ERROR `&u8` cannot be cached
+}
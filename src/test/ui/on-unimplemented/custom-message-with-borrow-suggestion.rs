@@ -0,0 +1,19 @@
+// Check that a custom primary message is kept when suggesting to borrow the argument.
+
+#![feature(rustc_attrs)]
+
+#[rustc_on_unimplemented(message = "`{Self}` cannot be used as a key")]
+trait Key {}
+
+trait Lookup {}
+
+impl<T: Key> Lookup for T {}
+
+impl Key for &String {}
+
+fn lookup<K: Lookup>(_: K) {}
+
+fn main() {
+    let name = String::from("key");
+    lookup(name); //~ ERROR `String` cannot be used as a key
+}
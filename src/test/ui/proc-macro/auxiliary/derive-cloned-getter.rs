@@ -0,0 +1,17 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(ClonedGetter)]
+pub fn derive(_input: TokenStream) -> TokenStream {
+    "impl<T> Wrapper<T> where T: Clone {
+        fn get_cloned(&self) -> T {
+            self.0.clone()
+        }
+    }".parse().unwrap()
+}
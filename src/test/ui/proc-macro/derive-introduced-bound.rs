@@ -0,0 +1,17 @@
+// aux-build:derive-cloned-getter.rs
+
+// Check that when a bound introduced by a derive isn't satisfied, we point at the derive.
+
+#[macro_use]
+extern crate derive_cloned_getter;
+
+#[derive(ClonedGetter)]
+struct Wrapper<T>(T);
+
+struct NotClone;
+
+fn main() {
+    let w = Wrapper(NotClone);
+    Wrapper::get_cloned(&w);
+    //~^ ERROR the trait bound `NotClone: Clone` is not satisfied
+}
@@ -0,0 +1,15 @@
+// `Wrapper<'a, T>` holds a `&'a T`, so using it requires `T: 'a`, which has to be spelled out in
+// an impl that doesn't otherwise imply it.
+
+struct Wrapper<'a, T>(&'a T);
+
+trait Project<'a, T> {
+    type Out;
+}
+
+impl<'a, T> Project<'a, T> for () {
+    type Out = Wrapper<'a, T>;
+    //~^ ERROR the parameter type `T` may not live long enough
+}
+
+fn main() {}
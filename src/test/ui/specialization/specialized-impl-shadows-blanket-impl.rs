@@ -0,0 +1,29 @@
+#![feature(specialization)] //~ WARN the feature `specialization` is incomplete
+
+// Check that we point out when a more specific impl whose bounds aren't satisfied was picked over
+// the blanket impl it specializes, which would have applied.
+
+trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl<T> Describe for T {
+    default fn describe(&self) -> &'static str {
+        "something"
+    }
+}
+
+impl<T: Copy> Describe for Vec<T> {
+    fn describe(&self) -> &'static str {
+        "a vector of copyable items"
+    }
+}
+
+fn describe<D: Describe>(d: &D) -> &'static str {
+    d.describe()
+}
+
+fn main() {
+    describe(&vec![String::new()]);
+    //~^ ERROR the trait bound `String: Copy` is not satisfied
+}
@@ -0,0 +1,24 @@
+// Check that locking or borrowing isn't suggested when only the value inside implements the
+// trait, and not the guard that gives access to it.
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for u32 {
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn describe(_: impl Describe) {}
+
+fn main() {
+    describe(Mutex::new(1u32));
+    //~^ ERROR the trait bound `Mutex<u32>: Describe` is not satisfied
+    describe(RefCell::new(1u32));
+    //~^ ERROR the trait bound `RefCell<u32>: Describe` is not satisfied
+}
@@ -0,0 +1,20 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::sync::Mutex;
+
+fn show(_: impl Display) {}
+
+fn main() {
+    let m = Mutex::new(1u32);
+    show(m);
+    //~^ ERROR `Mutex<u32>` doesn't implement `std::fmt::Display`
+
+    let c = RefCell::new(1u32);
+    show(c);
+    //~^ ERROR `RefCell<u32>` doesn't implement `std::fmt::Display`
+
+    // The dereference has to be parenthesized before the `Mutex` can be locked.
+    let b = Box::new(Mutex::new(2u32));
+    show(*b);
+    //~^ ERROR `Mutex<u32>` doesn't implement `std::fmt::Display`
+}
@@ -0,0 +1,47 @@
+// When a bound isn't met by a wrapper struct but is met by exactly one of its fields, suggest
+// accessing that field.
+
+#![allow(dead_code)]
+
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for f64 {
+    fn describe(&self) -> String {
+        format!("{:.2}", self)
+    }
+}
+
+struct Meters(f64);
+
+struct Labeled {
+    label: String,
+    value: f64,
+}
+
+struct Point(f64, f64);
+
+fn show(_: impl Describe) {}
+
+fn show_ref<T: Describe>(_: &T) {}
+
+fn main() {
+    let m = Meters(1.0);
+    show(m);
+    //~^ ERROR the trait bound `Meters: Describe` is not satisfied
+
+    let l = Labeled { label: "height".to_string(), value: 1.0 };
+    show_ref(&l);
+    //~^ ERROR the trait bound `Labeled: Describe` is not satisfied
+
+    // Both fields would do, so neither is suggested.
+    let p = Point(1.0, 2.0);
+    show(p);
+    //~^ ERROR the trait bound `Point: Describe` is not satisfied
+
+    // The field can't be appended to a dereference without parentheses.
+    let b = Box::new(Meters(2.0));
+    show(*b);
+    //~^ ERROR the trait bound `Meters: Describe` is not satisfied
+}
@@ -0,0 +1,31 @@
+// Check that we suggest slicing an array, or copying it into a `Vec`, when a bound fails on the
+// array but holds for `&[T]` or `Vec<T>`, and that `to_vec` isn't suggested for non-`Clone` items.
+
+trait Checksum {}
+
+impl<'a> Checksum for &'a [u8] {}
+
+trait Store {}
+
+impl<T> Store for Vec<T> {}
+
+struct Token;
+
+fn checksum<C: Checksum>(_: C) {}
+
+fn store<S: Store>(_: S) {}
+
+fn main() {
+    let bytes: [u8; 4] = [1, 2, 3, 4];
+    checksum(bytes);
+    //~^ ERROR the trait bound `[u8; 4]: Checksum` is not satisfied
+    store(bytes);
+    //~^ ERROR the trait bound `[u8; 4]: Store` is not satisfied
+    store([Token, Token]);
+    //~^ ERROR the trait bound `[Token; 2]: Store` is not satisfied
+
+    // The dereference has to be parenthesized before it can be sliced.
+    let boxed = Box::new([5u8; 2]);
+    checksum(*boxed);
+    //~^ ERROR the trait bound `[u8; 2]: Checksum` is not satisfied
+}
@@ -0,0 +1,17 @@
+// Check that we suggest borrowing an argument that is already a borrow when the bound only
+// holds for a reference to a reference.
+
+trait Marker {}
+
+trait Visit {}
+
+impl<T: Marker> Visit for T {}
+
+impl<T> Marker for &&T {}
+
+fn check<V: Visit>(_: V) {}
+
+fn main() {
+    let x = 0u8;
+    check(&x); //~ ERROR the trait bound `&u8: Visit` is not satisfied
+}
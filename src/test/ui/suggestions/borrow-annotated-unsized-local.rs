@@ -0,0 +1,11 @@
+// Check that when suggesting to borrow the initializer of an unsized local, we also suggest
+// borrowing its type annotation.
+
+fn foo(boxed: Box<str>, slice: &[u8]) {
+    let s: str = *boxed;
+    //~^ ERROR the size for values of type `str` cannot be known at compilation time
+    let v: [u8] = slice[1..];
+    //~^ ERROR the size for values of type `[u8]` cannot be known at compilation time
+}
+
+fn main() {}
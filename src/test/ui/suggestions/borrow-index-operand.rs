@@ -0,0 +1,12 @@
+// run-rustfix
+// A `HashMap` can only be indexed by a reference to a key, so suggest borrowing the key.
+
+use std::collections::HashMap;
+
+fn main() {
+    let mut ages: HashMap<String, u32> = HashMap::new();
+    ages.insert("ferris".to_string(), 11);
+    let name = "ferris".to_string();
+    let _age = ages[name];
+    //~^ ERROR the type `HashMap<String, u32>` cannot be indexed by `String`
+}
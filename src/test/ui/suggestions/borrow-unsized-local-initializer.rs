@@ -0,0 +1,17 @@
+// Check that we suggest borrowing the initializer of an unsized local when it is a dereference or
+// a field access.
+
+struct Tail {
+    data: [u8],
+}
+
+fn foo(boxed: Box<str>, slice: &[u8], tail: &Tail) {
+    let s = *boxed;
+    //~^ ERROR the size for values of type `str` cannot be known at compilation time
+    let v = *slice;
+    //~^ ERROR the size for values of type `[u8]` cannot be known at compilation time
+    let d = tail.data;
+    //~^ ERROR the size for values of type `[u8]` cannot be known at compilation time
+}
+
+fn main() {}
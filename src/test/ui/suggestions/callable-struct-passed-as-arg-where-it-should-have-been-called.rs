@@ -0,0 +1,21 @@
+#![feature(unboxed_closures, fn_traits)]
+
+trait Number {}
+
+impl Number for i32 {}
+
+struct Adder(i32);
+
+impl FnOnce<(i32,)> for Adder {
+    type Output = i32;
+    extern "rust-call" fn call_once(self, (x,): (i32,)) -> i32 {
+        self.0 + x
+    }
+}
+
+fn takes_number(n: impl Number) {}
+
+fn main() {
+    let adder = Adder(1);
+    takes_number(adder); //~ ERROR E0277
+}
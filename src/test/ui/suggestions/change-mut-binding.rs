@@ -0,0 +1,29 @@
+// Suggesting `&mut` for a borrow of a binding that isn't `mut` also suggests making it `mut`, and
+// nothing is suggested when the place can't be borrowed mutably at all.
+
+trait Trait {}
+
+struct S;
+
+struct Wrapper {
+    s: S,
+}
+
+impl Trait for &mut S {}
+
+fn foo<X: Trait>(_: X) {}
+
+fn by_ref(w: &Wrapper) {
+    foo(&w.s); //~ ERROR the trait bound `&S: Trait` is not satisfied
+}
+
+fn main() {
+    let s = S;
+    foo(&s); //~ ERROR the trait bound `&S: Trait` is not satisfied
+
+    let w = Wrapper { s: S };
+    foo(&w.s); //~ ERROR the trait bound `&S: Trait` is not satisfied
+
+    let mut m = S;
+    foo(&m); //~ ERROR the trait bound `&S: Trait` is not satisfied
+}
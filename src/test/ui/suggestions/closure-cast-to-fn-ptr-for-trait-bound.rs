@@ -0,0 +1,13 @@
+// A closure that captures nothing isn't coerced to a function pointer to satisfy a trait bound,
+// so suggest casting it explicitly.
+
+trait Handler {}
+
+impl Handler for fn(i32) -> i32 {}
+
+fn register<H: Handler>(_: H) {}
+
+fn main() {
+    register(|x: i32| x + 1);
+    //~^ ERROR the trait bound
+}
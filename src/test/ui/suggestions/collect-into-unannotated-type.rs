@@ -0,0 +1,8 @@
+// Check that we suggest a collection to `collect` into when nothing constrains its type, and
+// point out the other common collections that the items could be gathered into.
+
+fn main() {
+    let words = ["hello", "world"];
+    let shouted = words.iter().map(|word| word.to_uppercase()).collect();
+    //~^ ERROR type annotations needed
+}
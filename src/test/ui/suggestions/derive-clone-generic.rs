@@ -0,0 +1,31 @@
+// Check that a type parameter that doesn't implement `Clone` is restricted rather than annotated,
+// and that deriving is suggested for the concrete types that need it, even in generic code.
+
+fn needs_clone<T: Clone>(_: T) {}
+
+#[derive(Clone)]
+struct Wrapper<T>(T);
+
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+struct Inner;
+
+fn wrap<T>(w: Wrapper<T>) {
+    needs_clone(w);
+    //~^ ERROR the trait bound `T: Clone` is not satisfied
+}
+
+fn pair<T>(p: Pair<T>) {
+    needs_clone(p);
+    //~^ ERROR the trait bound `Pair<T>: Clone` is not satisfied
+}
+
+fn wrap_inner<T>(_: T, w: Wrapper<Inner>) {
+    needs_clone(w);
+    //~^ ERROR the trait bound `Inner: Clone` is not satisfied
+}
+
+fn main() {}
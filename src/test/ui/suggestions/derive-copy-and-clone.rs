@@ -0,0 +1,26 @@
+// Check that deriving `Copy` is suggested along with `Clone` when a type implements neither, and
+// that a field that isn't `Copy` is pointed at instead when the derive wouldn't work.
+
+fn assert_copy<T: Copy>() {}
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Clone)]
+struct Meters(f64);
+
+struct Named {
+    id: u32,
+    name: String,
+}
+
+fn main() {
+    assert_copy::<Point>();
+    //~^ ERROR the trait bound `Point: Copy` is not satisfied
+    assert_copy::<Meters>();
+    //~^ ERROR the trait bound `Meters: Copy` is not satisfied
+    assert_copy::<Named>();
+    //~^ ERROR the trait bound `Named: Copy` is not satisfied
+}
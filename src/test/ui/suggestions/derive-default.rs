@@ -0,0 +1,31 @@
+// Check that deriving `Default` is suggested for a struct whose fields are all `Default`, and
+// that the fields that aren't are pointed at instead when the derive wouldn't work.
+
+fn assert_default<T: Default>() {}
+
+struct NoDefault;
+
+struct Config {
+    verbose: bool,
+    level: u8,
+}
+
+struct Handle {
+    id: u32,
+    inner: NoDefault,
+}
+
+struct Pair {
+    left: NoDefault,
+    right: NoDefault,
+    count: usize,
+}
+
+fn main() {
+    assert_default::<Config>();
+    //~^ ERROR the trait bound `Config: Default` is not satisfied
+    assert_default::<Handle>();
+    //~^ ERROR the trait bound `Handle: Default` is not satisfied
+    assert_default::<Pair>();
+    //~^ ERROR the trait bound `Pair: Default` is not satisfied
+}
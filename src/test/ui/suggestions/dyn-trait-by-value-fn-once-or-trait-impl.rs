@@ -0,0 +1,25 @@
+// A `dyn FnOnce` argument can only be called when it's owned, so it is boxed rather than
+// borrowed, and the signature of a trait impl method is left alone as it has to match the trait.
+
+trait Shape {}
+
+fn run(task: dyn FnOnce()) {}
+//~^ ERROR the size for values of type `(dyn FnOnce() + 'static)` cannot be known
+
+trait Canvas {
+    fn draw(&self, shape: dyn Shape);
+    fn first(&self) -> dyn Shape;
+}
+
+struct Screen;
+
+impl Canvas for Screen {
+    fn draw(&self, shape: dyn Shape) {}
+    //~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known
+    fn first(&self) -> dyn Shape {
+        //~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known
+        loop {}
+    }
+}
+
+fn main() {}
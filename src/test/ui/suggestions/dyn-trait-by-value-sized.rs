@@ -0,0 +1,31 @@
+// A trait object can't be passed, returned or stored by value, so suggest putting it behind the
+// kind of pointer, or replacing it with the kind of type, that each position allows.
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Canvas;
+
+fn describe(shape: dyn Shape) {}
+//~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known at compilation time
+
+impl Canvas {
+    fn first(&self) -> dyn Shape {
+        //~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known
+        loop {}
+    }
+}
+
+trait Palette {
+    // `impl Trait` can't be returned from a trait method.
+    fn pick(&self) -> dyn Shape {
+        //~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known
+        loop {}
+    }
+}
+
+fn main() {
+    let shape: dyn Shape;
+    //~^ ERROR the size for values of type `(dyn Shape + 'static)` cannot be known
+}
@@ -0,0 +1,33 @@
+// Check that we suggest taking a trait object when one is passed to a function that is generic
+// over the trait.
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Circle(f64);
+struct Square(f64);
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.0 * self.0
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+fn print_area<T: Shape>(shape: T) {
+    println!("{}", shape.area());
+}
+
+fn main() {
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Circle(1.0)), Box::new(Square(2.0))];
+    for shape in shapes {
+        print_area(shape);
+        //~^ ERROR the trait bound `Box<dyn Shape>: Shape` is not satisfied
+    }
+}
@@ -0,0 +1,12 @@
+// Check that we suggest flattening an `Option` of a collection when the collection's items are
+// what is expected.
+
+fn sum<I: IntoIterator<Item = u32>>(items: I) -> u32 {
+    items.into_iter().sum()
+}
+
+fn main() {
+    let numbers: Option<Vec<u32>> = Some(vec![1, 2, 3]);
+    sum(numbers);
+    //~^ ERROR type mismatch resolving `<Option<Vec<u32>> as IntoIterator>::Item == u32`
+}
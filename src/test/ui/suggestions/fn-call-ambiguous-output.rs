@@ -0,0 +1,18 @@
+// Check that calling a function is only mentioned in a help, rather than suggested as an edit,
+// when it isn't known whether its return value would satisfy the bound.
+
+trait Describe {}
+
+impl Describe for u32 {}
+impl Describe for String {}
+
+fn describe<T: Describe>(_: T) {}
+
+fn parse<T: Default>() -> T {
+    T::default()
+}
+
+fn main() {
+    describe(parse);
+    //~^ ERROR the trait bound `fn() -> _ {parse::<_>}: Describe` is not satisfied
+}
@@ -0,0 +1,18 @@
+trait T {}
+
+struct S;
+
+impl T for S {}
+
+fn bar(f: impl T) {}
+
+fn make() -> S {
+    S
+}
+
+fn main() {
+    let ptr: fn() -> S = make;
+    bar(ptr); //~ ERROR E0277
+    let closure = |x: u32| S;
+    bar(closure); //~ ERROR E0277
+}
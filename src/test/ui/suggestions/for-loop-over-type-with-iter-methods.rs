@@ -0,0 +1,26 @@
+// Check that we suggest calling the appropriate inherent iteration method when the head of a
+// `for` loop doesn't implement `IntoIterator`.
+
+struct Bag {
+    items: Vec<u32>,
+}
+
+impl Bag {
+    fn iter(&self) -> std::slice::Iter<'_, u32> {
+        self.items.iter()
+    }
+
+    fn iter_mut(&mut self) -> std::slice::IterMut<'_, u32> {
+        self.items.iter_mut()
+    }
+}
+
+fn main() {
+    let mut bag = Bag { items: vec![1, 2, 3] };
+    for _ in &bag {}
+    //~^ ERROR `&Bag` is not an iterator
+    for x in &mut bag {
+        //~^ ERROR `&mut Bag` is not an iterator
+        *x += 1;
+    }
+}
@@ -0,0 +1,16 @@
+use std::io::{Read, Seek, Write};
+
+fn needs_read_seek<T: Read + Seek>(_: T) {}
+
+fn read_only(x: impl Read) {
+    needs_read_seek(x);
+    //~^ ERROR the trait bound `impl Read: Seek` is not satisfied
+}
+
+fn write_only(x: impl Write) {
+    needs_read_seek(x);
+    //~^ ERROR the trait bound `impl Write: Read` is not satisfied
+    //~| ERROR the trait bound `impl Write: Seek` is not satisfied
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+// Check that we suggest `cloned` when an iterator over references was cloned with `clone` and
+// the resulting items don't satisfy a bound that their clones would.
+
+fn main() {
+    let v = vec![String::new()];
+    let owned: Vec<String> = v.iter().clone().collect();
+    //~^ ERROR a value of type `Vec<String>` cannot be built
+}
@@ -0,0 +1,8 @@
+// Check that we suggest `into_iter` when an owned collection was iterated with `iter` and the
+// resulting references don't satisfy a bound that the items themselves would.
+
+fn main() {
+    let v = vec![String::new()];
+    let owned: Vec<String> = v.iter().collect();
+    //~^ ERROR a value of type `Vec<String>` cannot be built
+}
@@ -0,0 +1,36 @@
+// Check that we suggest annotating an unannotated `let` binding when its type being ambiguous is
+// the reason a trait bound can't be satisfied, unless the error already points at the binding.
+
+use std::marker::PhantomData;
+
+struct Marker<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> Marker<T> {
+    fn new() -> Self {
+        Marker(PhantomData)
+    }
+}
+
+trait Foo {}
+impl Foo for Marker<u8> {}
+impl Foo for Marker<u16> {}
+
+fn foo<X: Foo>(_: &X) {}
+
+fn main() {
+    let x = Marker::new();
+    foo(&x);
+    //~^ ERROR type annotations needed
+}
+
+trait Bar {}
+impl Bar for (Marker<u8>, u8) {}
+impl Bar for (Marker<u16>, u8) {}
+
+fn bar<X: Bar>(_: &X) {}
+
+fn tuple() {
+    let y = Marker::new();
+    bar(&(y, 0u8));
+    //~^ ERROR type annotations needed
+}
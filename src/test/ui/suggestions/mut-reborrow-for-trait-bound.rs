@@ -0,0 +1,32 @@
+// run-rustfix
+// A `&mut` reference that only reaches a bound as a shared reference is reborrowed, rather than
+// borrowed once more or moved.
+
+trait Sink {
+    fn push_byte(self, byte: u8);
+}
+
+impl Sink for &mut Vec<u8> {
+    fn push_byte(self, byte: u8) {
+        self.push(byte);
+    }
+}
+
+fn fill<S: Sink>(sink: S) {
+    sink.push_byte(0);
+}
+
+struct Writer<'a> {
+    buf: &'a mut Vec<u8>,
+}
+
+fn fill_twice(buf: &mut Vec<u8>) {
+    fill(&buf); //~ ERROR the trait bound `&&mut Vec<u8>: Sink` is not satisfied
+    fill(buf);
+}
+
+fn fill_writer(writer: &mut Writer<'_>) {
+    fill(&writer.buf); //~ ERROR the trait bound `&&mut Vec<u8>: Sink` is not satisfied
+}
+
+fn main() {}
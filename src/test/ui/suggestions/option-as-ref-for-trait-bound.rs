@@ -0,0 +1,14 @@
+// Check that we suggest `as_ref` when a bound fails on an `Option` but holds for an `Option` of a
+// reference to its value.
+
+trait Describe {}
+
+impl<'a> Describe for Option<&'a String> {}
+
+fn describe<T: Describe>(_: T) {}
+
+fn main() {
+    let name: Option<String> = Some(String::new());
+    describe(name);
+    //~^ ERROR the trait bound `Option<String>: Describe` is not satisfied
+}
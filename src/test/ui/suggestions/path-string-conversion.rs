@@ -0,0 +1,21 @@
+use std::path::{Path, PathBuf};
+
+trait Resolve<Base: ?Sized> {}
+
+impl<'a> Resolve<Path> for &'a Path {}
+impl Resolve<Path> for PathBuf {}
+
+fn resolve(_: impl Resolve<Path>) {}
+
+trait Describe<T: ?Sized> {}
+
+impl Describe<str> for String {}
+
+fn describe(_: impl Describe<str>) {}
+
+fn main() {
+    let name = String::from("config.toml");
+    resolve(name); //~ ERROR E0277
+    let path = PathBuf::from("config.toml");
+    describe(path); //~ ERROR E0277
+}
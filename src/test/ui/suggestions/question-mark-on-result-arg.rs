@@ -0,0 +1,49 @@
+// Check that we suggest `?` on a `Result` or `Option` argument whose value would satisfy the
+// bound, but only when the enclosing function can propagate its error, parenthesizing the
+// argument where `?` would otherwise bind to part of it.
+
+use std::num::ParseIntError;
+
+trait Describe {}
+
+impl Describe for u32 {}
+
+fn describe<T: Describe>(_: T) {}
+
+struct ConvertsError;
+
+impl From<ParseIntError> for ConvertsError {
+    fn from(_: ParseIntError) -> Self {
+        ConvertsError
+    }
+}
+
+struct OtherError;
+
+fn converts(s: &str) -> Result<(), ConvertsError> {
+    let parsed = s.parse::<u32>();
+    describe(parsed);
+    //~^ ERROR the trait bound `Result<u32, ParseIntError>: Describe` is not satisfied
+    Ok(())
+}
+
+fn does_not_convert(s: &str) -> Result<(), OtherError> {
+    let parsed = s.parse::<u32>();
+    describe(parsed);
+    //~^ ERROR the trait bound `Result<u32, ParseIntError>: Describe` is not satisfied
+    Ok(())
+}
+
+fn option(n: Option<u32>) -> Option<()> {
+    describe(n);
+    //~^ ERROR the trait bound `Option<u32>: Describe` is not satisfied
+    Some(())
+}
+
+fn deref(n: &Option<u32>) -> Option<()> {
+    describe(*n);
+    //~^ ERROR the trait bound `Option<u32>: Describe` is not satisfied
+    Some(())
+}
+
+fn main() {}
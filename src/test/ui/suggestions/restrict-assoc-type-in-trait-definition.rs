@@ -0,0 +1,22 @@
+// run-rustfix
+// Check that when a bound on `Self::Item` is missing within its own trait, the bound is suggested
+// on the declaration of the associated type.
+
+#![allow(dead_code)]
+
+fn duplicate<T: Clone>(value: T) -> (T, T) {
+    (value.clone(), value)
+}
+
+trait Source {
+    type Item;
+
+    fn next(&mut self) -> Self::Item;
+
+    fn next_pair(&mut self) -> (Self::Item, Self::Item) {
+        duplicate(self.next())
+        //~^ ERROR the trait bound `<Self as Source>::Item: Clone` is not satisfied
+    }
+}
+
+fn main() {}
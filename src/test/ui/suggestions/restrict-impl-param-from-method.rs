@@ -0,0 +1,15 @@
+// Check that a bound missing on an `impl`'s type parameter where it is used in one of its methods
+// is suggested on the `impl` header, even when the method has type parameters of its own.
+
+fn needs_clone<U: Clone>(_: &U) {}
+
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn check<U>(&self, _: U) {
+        needs_clone(&self.0);
+        //~^ ERROR the trait bound `T: Clone` is not satisfied
+    }
+}
+
+fn main() {}
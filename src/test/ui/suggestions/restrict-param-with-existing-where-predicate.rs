@@ -0,0 +1,18 @@
+// run-rustfix
+
+#[allow(unused)]
+use std::fmt::Debug;
+
+#[allow(dead_code)]
+fn print_both<T, U, V>(t: T, u: U, v: V)
+where
+    T: Clone,
+    U: Copy,
+    V: Debug,
+{
+    println!("{:?} {:?} {:?}", t, u, v);
+    //~^ ERROR `T` doesn't implement `Debug`
+    //~| ERROR `U` doesn't implement `Debug`
+}
+
+fn main() {}
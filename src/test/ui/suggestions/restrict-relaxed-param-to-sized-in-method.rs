@@ -0,0 +1,15 @@
+// Check that when a type parameter relaxed with `?Sized` on an `impl` has to be `Sized` in one of
+// its methods, we suggest requiring `Sized` for that method only.
+
+fn needs_sized<T>(_: &T) {}
+
+struct Wrapper<T: ?Sized>(Box<T>);
+
+impl<T: ?Sized> Wrapper<T> {
+    fn check(&self) {
+        needs_sized::<T>(&*self.0);
+        //~^ ERROR the size for values of type `T` cannot be known at compilation time
+    }
+}
+
+fn main() {}
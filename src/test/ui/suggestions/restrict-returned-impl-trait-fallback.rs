@@ -0,0 +1,17 @@
+// edition:2018
+
+// Check that when the `impl Trait` can't be given the missing bound, such as the future returned
+// by an `async fn`, the usual help is still shown.
+
+trait Describe {}
+
+impl Describe for u8 {}
+
+fn describe<T: Describe>(_: T) {}
+
+async fn ready() {}
+
+fn main() {
+    describe(ready());
+    //~^ ERROR the trait bound `impl Future: Describe` is not satisfied
+}
@@ -0,0 +1,15 @@
+// Check that we suggest adding a missing bound to a returned `impl Trait`.
+
+fn numbers() -> impl Iterator<Item = u32> {
+    vec![1, 2, 3].into_iter()
+}
+
+fn iterate_twice<I: Iterator<Item = u32> + Clone>(iter: I) {
+    for _ in iter.clone() {}
+    for _ in iter {}
+}
+
+fn main() {
+    iterate_twice(numbers());
+    //~^ ERROR the trait bound `impl Iterator: Clone` is not satisfied
+}
@@ -0,0 +1,18 @@
+// Check that we suggest adding a `where` clause instead of appending to a long list of bounds.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+fn needs_clone<T: Clone>(_: T) {}
+
+fn foo<T: Debug + Hash + Eq>(t: T) {
+    needs_clone(t);
+    //~^ ERROR the trait bound `T: Clone` is not satisfied
+}
+
+fn bar<T: Debug + Hash>(t: T) {
+    needs_clone(t);
+    //~^ ERROR the trait bound `T: Clone` is not satisfied
+}
+
+fn main() {}
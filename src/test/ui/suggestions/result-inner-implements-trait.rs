@@ -0,0 +1,13 @@
+// Check that we point out when a bound fails on a `Result` but holds for the value inside it.
+
+trait Describe {}
+
+impl Describe for u32 {}
+
+fn describe<T: Describe>(_: T) {}
+
+fn main() {
+    let parsed: Result<u32, String> = Ok(1);
+    describe(parsed);
+    //~^ ERROR the trait bound `Result<u32, String>: Describe` is not satisfied
+}
@@ -0,0 +1,20 @@
+// When a local trait isn't implemented for a local type, suggest an `impl` block with stubs for
+// the items that have to be provided.
+
+trait Shape {
+    const SIDES: u32;
+    type Unit;
+    fn area(&self) -> f64;
+    fn name(&self) -> &'static str {
+        "shape"
+    }
+}
+
+struct Square(f64);
+
+fn print_area(_: impl Shape) {}
+
+fn main() {
+    print_area(Square(2.0));
+    //~^ ERROR the trait bound `Square: Shape` is not satisfied
+}
@@ -0,0 +1,23 @@
+// `Table<String, u32>` was written where `Table<u32, String>` was meant, which only shows up as
+// `String` not meeting the bound on keys. Suggest swapping the type arguments back.
+
+trait Key {}
+
+impl Key for u32 {}
+
+struct Table<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+trait Lookup {}
+
+impl<K: Key, V> Lookup for Table<K, V> {}
+
+fn index<T: Lookup>(_: &T) {}
+
+fn build(table: &Table<String, u32>) {
+    index(table);
+    //~^ ERROR the trait bound `String: Key` is not satisfied
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// compile-flags: -Z terse-trait-suggestions
+
+trait Trait {}
+struct Struct;
+impl Trait for Struct {}
+impl Trait for u32 {}
+
+fn same() -> dyn Trait { Struct }
+//~^ ERROR return type cannot have an unboxed trait object
+
+fn different() -> dyn Trait {
+    //~^ ERROR return type cannot have an unboxed trait object
+    if true {
+        return Struct;
+    }
+    42u32
+}
+
+fn print<T>(t: T) {
+    println!("{:?}", t);
+    //~^ ERROR `T` doesn't implement `Debug`
+}
+
+fn main() {}
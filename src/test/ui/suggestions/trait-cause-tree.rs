@@ -0,0 +1,24 @@
+// compile-flags: -Z trait-cause-tree
+// With `-Z trait-cause-tree`, the requirements that led to an unsatisfied trait bound are
+// numbered and indented as a tree, from the impl that needs `Foo: Describe` out to the bound on
+// `print`, instead of being noted as a flat list.
+
+trait Describe {}
+trait Render {}
+trait Show {}
+trait Print {}
+
+struct Foo;
+struct Wrapper<T>(T);
+
+impl<T: Describe> Render for Wrapper<T> {}
+impl<T: Render> Show for Option<T> {}
+impl<T: Show> Print for Vec<T> {}
+
+fn print<T: Print>(_: T) {}
+
+fn main() {
+    let v = vec![Some(Wrapper(Foo))];
+    print(v);
+    //~^ ERROR the trait bound `Foo: Describe` is not satisfied
+}
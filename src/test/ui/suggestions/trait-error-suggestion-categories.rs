@@ -0,0 +1,32 @@
+// compile-flags: --error-format pretty-json --json=diagnostic-short
+
+// Check that the suggestions made for unmet trait bounds are tagged with the kind of fix they
+// are, so that tools can group them.
+
+trait Describe {}
+
+impl Describe for u32 {}
+
+fn describe<T: Describe>(_: T) {}
+
+fn needs_clone<T: Clone>(_: T) {}
+
+fn answer() -> u32 {
+    42
+}
+
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+fn pair<T>(p: Pair<T>) {
+    needs_clone(p);
+}
+
+fn main() {
+    describe(answer);
+    describe(Meters(3));
+}
+
+struct Meters(u32);
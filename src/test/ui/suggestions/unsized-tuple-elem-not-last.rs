@@ -0,0 +1,9 @@
+fn foo<T: ?Sized>(_: &T) {
+    let _x: (T, u8);
+    //~^ ERROR the size for values of type `T` cannot be known at compilation time
+}
+
+fn bar(_: &(str, u8)) {}
+//~^ ERROR the size for values of type `str` cannot be known at compilation time
+
+fn main() {}
@@ -0,0 +1,9 @@
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait Api: private::Sealed {}
+
+pub trait Marker {}
+
+pub trait Open: Marker {}
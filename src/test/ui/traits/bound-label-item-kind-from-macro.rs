@@ -0,0 +1,21 @@
+// Check that the item whose bound isn't met is labeled with its kind, also when it is generated
+// by a macro and its name comes from the macro's caller.
+
+trait Shape {}
+
+macro_rules! shaped_fn {
+    ($name:ident) => {
+        fn $name<T>(_: T)
+        where
+            T: Shape,
+        {
+        }
+    };
+}
+
+shaped_fn!(area);
+
+fn main() {
+    area(1u8);
+    //~^ ERROR the trait bound `u8: Shape` is not satisfied
+}
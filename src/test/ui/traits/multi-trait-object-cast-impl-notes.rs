@@ -0,0 +1,15 @@
+// Casting to a trait object with several components checks each of them; the requirement chain
+// through the impls of `Named` should only be noted once.
+
+trait Named {}
+
+struct Tag<T>(T);
+struct Wrapper<T>(T);
+
+impl<T: Named> Named for Tag<T> {}
+impl<T: Named> Named for Wrapper<T> {}
+
+fn main() {
+    let _: Box<dyn Named + Send + Sync> = Box::new(Wrapper(Tag(0u8)));
+    //~^ ERROR the trait bound `u8: Named` is not satisfied
+}
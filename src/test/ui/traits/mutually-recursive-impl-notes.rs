@@ -0,0 +1,14 @@
+// Two generic types whose impls require each other must only have the requirements on their
+// impls noted once, and the notes must not recurse without bound.
+
+struct Tree<T>(T, *const u8);
+struct Forest<T>(T, *const u8);
+
+unsafe impl<T> Send for Tree<T> where Forest<T>: Sync {}
+unsafe impl<T> Sync for Forest<T> where Tree<T>: Send, T: Send {}
+
+fn check<V: Send>() {}
+
+fn main() {
+    check::<Tree<*const u8>>(); //~ ERROR `*const u8` cannot be sent between threads safely
+}
@@ -0,0 +1,16 @@
+// When one of several casts to a trait object fails, point at the cast that requires the bound.
+
+trait Shape {}
+
+struct Circle;
+struct Label;
+
+impl Shape for Circle {}
+
+fn main() {
+    let circle = Circle;
+    let label = Label;
+    let _a = &circle as &dyn Shape;
+    let _b = &label as &dyn Shape;
+    //~^ ERROR the trait bound `Label: Shape` is not satisfied
+}
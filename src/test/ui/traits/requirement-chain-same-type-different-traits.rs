@@ -0,0 +1,17 @@
+// `Node` is required to be `Send`, which through `&'static Node` requires it to be `Sync` too.
+// Those are different requirements on the same type, so the whole chain has to be noted instead
+// of being cut short as if it was recursive.
+
+use std::cell::Cell;
+
+struct Node {
+    value: Cell<i32>,
+    parent: Option<&'static Node>,
+}
+
+fn is_send<T: Send>() {}
+
+fn main() {
+    is_send::<Node>();
+    //~^ ERROR `Cell<i32>` cannot be shared between threads safely
+}
@@ -0,0 +1,18 @@
+// aux-build:sealed_trait.rs
+
+// Check that implementing a trait whose supertrait can't be named outside of its crate points out
+// that the trait is sealed, and that an ordinary missing supertrait doesn't.
+
+extern crate sealed_trait;
+
+use sealed_trait::{Api, Open};
+
+struct Foo;
+
+impl Api for Foo {}
+//~^ ERROR the trait bound `Foo: sealed_trait::private::Sealed` is not satisfied
+
+impl Open for Foo {}
+//~^ ERROR the trait bound `Foo: Marker` is not satisfied
+
+fn main() {}
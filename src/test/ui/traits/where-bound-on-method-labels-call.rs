@@ -0,0 +1,26 @@
+// Check that when a `where` bound on a trait method isn't met by one of the call's arguments, the
+// call to the method is labeled along with the bound.
+
+trait Store {
+    fn put<T>(&mut self, value: T)
+    where
+        T: Clone;
+}
+
+struct Shelf;
+
+impl Store for Shelf {
+    fn put<T>(&mut self, _value: T)
+    where
+        T: Clone,
+    {
+    }
+}
+
+struct NotClone;
+
+fn main() {
+    let mut shelf = Shelf;
+    Store::put(&mut shelf, NotClone);
+    //~^ ERROR the trait bound `NotClone: Clone` is not satisfied
+}
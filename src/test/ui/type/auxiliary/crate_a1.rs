@@ -9,3 +9,4 @@ pub fn bar() -> Box<Bar> {
 
 pub fn try_foo(x: Foo){}
 pub fn try_bar(x: Box<Bar>){}
+pub fn with_foo<F: Fn(Foo)>(_: F) {}
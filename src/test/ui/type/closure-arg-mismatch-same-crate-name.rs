@@ -0,0 +1,18 @@
+// aux-build:crate_a1.rs
+// aux-build:crate_a2.rs
+
+// Check that a closure argument mismatch between types with the same path from two different
+// crates points out that two versions of a crate may be in use.
+
+fn main() {
+    let print_foo = {
+        extern crate crate_a2 as a;
+        |_: a::Foo| {}
+    };
+    {
+        extern crate crate_a1 as a;
+        a::with_foo(print_foo);
+        //~^ ERROR type mismatch in closure arguments
+        //~| NOTE perhaps two different versions of crate `crate_a1` are being used?
+    }
+}
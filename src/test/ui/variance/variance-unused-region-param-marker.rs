@@ -0,0 +1,14 @@
+// Check that we show how to use an unused lifetime parameter in a `PhantomData` marker.
+
+struct Parser<'a> {
+    //~^ ERROR parameter `'a` is never used
+    input: String,
+}
+
+impl<'a> Parser<'a> {
+    fn next_token(&self) -> &'a str {
+        unimplemented!()
+    }
+}
+
+fn main() {}